@@ -0,0 +1,269 @@
+//! Processing many specs in one run, each with its own operation ID
+//! namespace.
+
+use anyhow::{anyhow, Result};
+use openapiv3::OpenAPI;
+use rayon::prelude::*;
+use walkdir::WalkDir;
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use crate::generate::gen_operation_ids;
+use crate::input::read_spec;
+use crate::opid::OperationIds;
+use crate::output;
+use crate::settings::Settings;
+use crate::validate::check_spec;
+use crate::warning::WarningFormat;
+
+/// Suffix of the outputs [`run_recursive`] writes next to its inputs. Files
+/// named like this are never picked up as inputs.
+const RECURSIVE_SUFFIX: &str = ".opids.json";
+
+/// How every spec of a batch is read, processed and written
+pub struct BatchOptions<'a> {
+  pub json5: bool,
+  pub settings: &'a Settings,
+  pub compact: bool,
+  pub overwrite: bool,
+  /// Reject unsupported OpenAPI versions instead of warning about them
+  pub strict: bool,
+  pub warning_format: WarningFormat,
+}
+
+/// What became of one spec of a batch
+enum Outcome {
+  Written(OperationIds),
+  /// The file is not a spec at all
+  Skipped(String),
+}
+
+/// Apply `f` to every item, on rayon's thread pool if `parallel` is set.
+/// The results are in the order of the items either way.
+pub fn process<T, R, F>(items: &[T], parallel: bool, f: F) -> Vec<R>
+where
+  T: Sync,
+  R: Send,
+  F: Fn(&T) -> R + Sync + Send,
+{
+  if parallel {
+    items.par_iter().map(f).collect()
+  } else {
+    items.iter().map(f).collect()
+  }
+}
+
+/// Read the spec at `input`, assign its operation IDs and write it to
+/// `out_path`. With `skip_non_specs`, files that do not parse as a spec are
+/// skipped instead of failing.
+fn process_file(
+  input: &Path,
+  out_path: &Path,
+  options: &BatchOptions,
+  skip_non_specs: bool,
+) -> Result<Outcome> {
+  let mut spec: OpenAPI = match read_spec(input, options.json5) {
+    Ok(spec) => spec,
+    Err(err) if skip_non_specs => return Ok(Outcome::Skipped(format!("{err:#}"))),
+    Err(err) => return Err(err),
+  };
+  let warnings = check_spec(&mut spec, options.strict)?;
+  let mut opids = gen_operation_ids(&mut spec, options.settings)?;
+  warnings
+    .into_iter()
+    .for_each(|warning| opids.add_warning(warning));
+  if let Some(dir) = out_path.parent() {
+    std::fs::create_dir_all(dir)?;
+  }
+  output::write_output(out_path, options.overwrite, |writer| {
+    output::write_spec(writer, &spec, options.compact)
+  })?;
+  Ok(Outcome::Written(opids))
+}
+
+/// Process every `(input, output)` pair in parallel. Warnings and errors
+/// are printed in the order of `jobs` once all specs are processed. Specs
+/// that fail do not keep the others from being written, but make the
+/// whole run fail. Returns how many warnings were printed.
+fn run_jobs(
+  jobs: &[(PathBuf, PathBuf)],
+  options: &BatchOptions,
+  skip_non_specs: bool,
+) -> Result<usize> {
+  let results = process(jobs, true, |(input, out_path)| {
+    process_file(input, out_path, options, skip_non_specs)
+  });
+
+  let mut failed = 0;
+  let mut printed = 0;
+  for ((input, _), result) in jobs.iter().zip(results) {
+    match result {
+      Ok(Outcome::Written(opids)) => {
+        printed += opids.warnings().len();
+        opids
+          .warnings()
+          .iter()
+          .for_each(|warning| match options.warning_format {
+            WarningFormat::Plain => eprintln!("{}: {warning}", input.display()),
+            format => eprintln!("{}", format.format(warning, input)),
+          })
+      }
+      Ok(Outcome::Skipped(reason)) => {
+        printed += 1;
+        eprintln!(
+          "{}: warning: skipped, not a spec: {reason}",
+          input.display()
+        )
+      }
+      Err(err) => {
+        failed += 1;
+        eprintln!("{}: error: {err:#}", input.display());
+      }
+    }
+  }
+  if failed > 0 {
+    return Err(anyhow!("{failed} of {} specs failed", jobs.len()));
+  }
+  Ok(printed)
+}
+
+/// Assign the operation IDs of every spec in `inputs` in parallel, and
+/// write the results into `out_dir` under their original file names.
+/// Returns how many warnings were printed.
+pub fn run(inputs: &[PathBuf], out_dir: &Path, options: &BatchOptions) -> Result<usize> {
+  let mut out_files: BTreeMap<&std::ffi::OsStr, &Path> = BTreeMap::new();
+  for input in inputs {
+    let file_name = input
+      .file_name()
+      .ok_or_else(|| anyhow!("{} is not a file", input.display()))?;
+    if let Some(other) = out_files.insert(file_name, input) {
+      return Err(anyhow!(
+        "{} and {} would both be written to {}",
+        other.display(),
+        input.display(),
+        out_dir.join(file_name).display()
+      ));
+    }
+  }
+
+  let jobs: Vec<_> = inputs
+    .iter()
+    .map(|input| {
+      let out_path = out_dir.join(input.file_name().unwrap_or_default());
+      (input.clone(), out_path)
+    })
+    .collect();
+  run_jobs(&jobs, options, false)
+}
+
+/// The JSON files below `dir`, sorted, leaving out outputs of earlier
+/// [`run_recursive`] runs
+fn find_specs(dir: &Path) -> Result<Vec<PathBuf>> {
+  let mut specs = Vec::new();
+  for entry in WalkDir::new(dir).sort_by_file_name() {
+    let entry = entry?;
+    let name = entry.file_name().to_string_lossy();
+    if entry.file_type().is_file()
+      && name.ends_with(".json")
+      && !name.ends_with(RECURSIVE_SUFFIX)
+    {
+      specs.push(entry.into_path());
+    }
+  }
+  Ok(specs)
+}
+
+/// Assign the operation IDs of every JSON spec below `dir`, each on its
+/// own. The results are written next to the inputs as `*.opids.json`, or
+/// into the same relative location below `out_dir` if given. JSON files
+/// that are not specs are skipped with a warning. Returns how many warnings
+/// were printed.
+pub fn run_recursive(
+  dir: &Path,
+  out_dir: Option<&Path>,
+  options: &BatchOptions,
+) -> Result<usize> {
+  let jobs: Vec<_> = find_specs(dir)?
+    .into_iter()
+    .map(|input| {
+      let out_path = match out_dir {
+        Some(out_dir) => out_dir.join(input.strip_prefix(dir).unwrap_or(&input)),
+        None => {
+          let stem = input.file_stem().unwrap_or_default().to_string_lossy();
+          input.with_file_name(format!("{stem}{RECURSIVE_SUFFIX}"))
+        }
+      };
+      (input, out_path)
+    })
+    .collect();
+  run_jobs(&jobs, options, true)
+}
+
+#[test]
+fn test_parallel_matches_sequential() {
+  let specs: Vec<String> = (0..16)
+    .map(|i| {
+      format!(
+        r#"{{
+          "/users": {{ "get": {{ "responses": {{}} }} }},
+          "/users{i}": {{ "get": {{ "responses": {{}} }} }},
+          "/users/{{id}}": {{ "get": {{ "operationId": "users{}_get", "responses": {{}} }} }}
+        }}"#,
+        i % 3
+      )
+    })
+    .collect();
+  let run = |parallel: bool| {
+    process(&specs, parallel, |paths| {
+      let mut spec = crate::spec::mk_spec(paths);
+      let opids = gen_operation_ids(&mut spec, &Settings::default()).unwrap();
+      (serde_json::to_string(&spec).unwrap(), opids.report())
+    })
+  };
+
+  let sequential = run(false);
+  assert_eq!(sequential.len(), specs.len());
+  assert_eq!(run(true), sequential);
+}
+
+#[test]
+fn test_run_recursive() {
+  let dir = std::env::temp_dir().join(format!("verge-recursive-{}", std::process::id()));
+  let _ = std::fs::remove_dir_all(&dir);
+  let spec = r#"{
+    "openapi": "3.0.1",
+    "info": { "title": "test", "version": "1" },
+    "paths": { "/users": { "get": { "responses": {} } } }
+  }"#;
+  std::fs::create_dir_all(dir.join("api/users")).unwrap();
+  std::fs::create_dir_all(dir.join("api/groups")).unwrap();
+  std::fs::write(dir.join("api/users/openapi.json"), spec).unwrap();
+  std::fs::write(dir.join("api/groups/openapi.json"), spec).unwrap();
+  std::fs::write(dir.join("api/package.json"), r#"{ "name": "docs" }"#).unwrap();
+  std::fs::write(dir.join("api/README.md"), "# docs").unwrap();
+
+  let settings = Settings::default();
+  let options = BatchOptions {
+    json5: false,
+    settings: &settings,
+    compact: false,
+    overwrite: false,
+    strict: false,
+    warning_format: WarningFormat::Plain,
+  };
+  run_recursive(&dir, None, &options).unwrap();
+  let output = std::fs::read_to_string(dir.join("api/users/openapi.opids.json")).unwrap();
+  assert!(output.contains("users_get"));
+  assert!(dir.join("api/groups/openapi.opids.json").exists());
+  assert!(!dir.join("api/package.opids.json").exists());
+
+  // outputs of the first run are no inputs of the second one
+  let out_dir = dir.join("out");
+  run_recursive(&dir.join("api"), Some(&out_dir), &options).unwrap();
+  assert!(out_dir.join("users/openapi.json").exists());
+  assert!(out_dir.join("groups/openapi.json").exists());
+  assert!(!out_dir.join("users/openapi.opids.json").exists());
+
+  std::fs::remove_dir_all(&dir).unwrap();
+}
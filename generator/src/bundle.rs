@@ -0,0 +1,314 @@
+use anyhow::{anyhow, Result};
+use serde_json::{Map, Value};
+
+use std::collections::{BTreeSet, HashMap};
+use std::path::{Path, PathBuf};
+
+use crate::input::parse_document;
+
+/// Replace every `$ref` to another file, like `./paths/users.json#/users`,
+/// by the value it points to, so the spec read from `file` becomes a single
+/// document. Relative paths are resolved against the directory of the file
+/// holding the reference. References within the spec itself, starting with
+/// `#`, are kept, while those within the referenced files are inlined as
+/// well, since the document they point into is not part of the result.
+/// Values of referenced files that refer to themselves, like a tree schema
+/// listing its children, cannot be inlined, so they are moved to the
+/// `components.schemas` of the spec and referred to there, as only schemas
+/// can be recursive. Only local files are supported, and references that
+/// only lead to each other are an error.
+pub fn bundle(value: &mut Value, file: &Path, json5: bool) -> Result<()> {
+  let taken = value
+    .pointer("/components/schemas")
+    .and_then(Value::as_object)
+    .map(|schemas| schemas.keys().cloned().collect())
+    .unwrap_or_default();
+  let mut bundler = Bundler {
+    json5,
+    documents: HashMap::new(),
+    resolving: Vec::new(),
+    hoisted: HashMap::new(),
+    schemas: Map::new(),
+    taken,
+  };
+  bundler.bundle(value, file, false)?;
+  if bundler.schemas.is_empty() {
+    return Ok(());
+  }
+  let schemas = value
+    .as_object_mut()
+    .and_then(|spec| {
+      spec
+        .entry("components")
+        .or_insert_with(|| Value::Object(Map::new()))
+        .as_object_mut()
+    })
+    .and_then(|components| {
+      components
+        .entry("schemas")
+        .or_insert_with(|| Value::Object(Map::new()))
+        .as_object_mut()
+    })
+    .ok_or_else(|| anyhow!("cannot add recursive schemas to {}", file.display()))?;
+  schemas.extend(bundler.schemas);
+  Ok(())
+}
+
+/// A reference to the component schema `name`
+fn schema_ref(name: &str) -> Value {
+  serde_json::json!({ "$ref": format!("#/components/schemas/{name}") })
+}
+
+struct Bundler {
+  json5: bool,
+  /// The referenced files read so far
+  documents: HashMap<PathBuf, Value>,
+  /// The references being inlined, to detect cycles
+  resolving: Vec<(PathBuf, String)>,
+  /// The component schemas recursive references were moved to
+  hoisted: HashMap<(PathBuf, String), String>,
+  /// The recursive schemas to add to the components
+  schemas: Map<String, Value>,
+  /// The names of component schemas, so moved ones do not replace others
+  taken: BTreeSet<String>,
+}
+
+impl Bundler {
+  /// Inline the references in `value`, which was read from `file`. In an
+  /// `external` file, references starting with `#` are inlined too.
+  fn bundle(&mut self, value: &mut Value, file: &Path, external: bool) -> Result<()> {
+    match value {
+      Value::Object(map) => {
+        if let Some(Value::String(reference)) = map.get("$ref") {
+          let (target, fragment) = reference
+            .split_once('#')
+            .unwrap_or((reference.as_str(), ""));
+          if !target.is_empty() || external {
+            let target = if target.is_empty() {
+              file.to_path_buf()
+            } else {
+              let target = file.parent().unwrap_or(Path::new("")).join(target);
+              // so cycles are noticed however the files spell each other
+              std::fs::canonicalize(&target).unwrap_or(target)
+            };
+            let fragment = fragment.to_string();
+            *value = self.resolve(&target, &fragment)?;
+            return Ok(());
+          }
+        }
+        for value in map.values_mut() {
+          self.bundle(value, file, external)?;
+        }
+      }
+      Value::Array(values) => {
+        for value in values {
+          self.bundle(value, file, external)?;
+        }
+      }
+      _ => {}
+    }
+    Ok(())
+  }
+
+  /// The value at the JSON pointer `fragment` of `file`, with its own
+  /// references inlined, or a reference to the component schema it was
+  /// moved to if it refers to itself
+  fn resolve(&mut self, file: &Path, fragment: &str) -> Result<Value> {
+    let reference = format!("{}#{fragment}", file.display());
+    let key = (file.to_path_buf(), fragment.to_string());
+    if let Some(name) = self.hoisted.get(&key) {
+      return Ok(schema_ref(name));
+    }
+    if self.resolving.contains(&key) {
+      let name = self.schema_name(file, fragment);
+      self.hoisted.insert(key, name.clone());
+      return Ok(schema_ref(&name));
+    }
+    if !self.documents.contains_key(file) {
+      let content = std::fs::read_to_string(file)
+        .map_err(|err| anyhow!("cannot read $ref target {}: {err}", file.display()))?;
+      let document = parse_document(&content, file, self.json5)?;
+      self.documents.insert(file.to_path_buf(), document);
+    }
+    let mut target = self.documents[file]
+      .pointer(fragment)
+      .cloned()
+      .ok_or_else(|| anyhow!("$ref target {reference} does not exist"))?;
+
+    self.resolving.push(key.clone());
+    let result = self.bundle(&mut target, file, true);
+    self.resolving.pop();
+    result?;
+    let Some(name) = self.hoisted.get(&key).cloned() else {
+      return Ok(target);
+    };
+    // a reference that only leads back to itself has nothing to move
+    if target == schema_ref(&name) {
+      return Err(anyhow!("circular $ref to {reference}"));
+    }
+    self.schemas.insert(name.clone(), target);
+    Ok(schema_ref(&name))
+  }
+
+  /// A free component schema name for the value at `fragment` of `file`,
+  /// after the last segment of `fragment`, or the file if it has none
+  fn schema_name(&mut self, file: &Path, fragment: &str) -> String {
+    let last = fragment
+      .rsplit('/')
+      .next()
+      .unwrap_or_default()
+      .replace("~1", "/")
+      .replace("~0", "~");
+    let base = if last.is_empty() {
+      file
+        .file_stem()
+        .unwrap_or_default()
+        .to_string_lossy()
+        .into_owned()
+    } else {
+      last
+    };
+    let base: String = base
+      .chars()
+      .map(|c| {
+        if c.is_ascii_alphanumeric() || matches!(c, '.' | '-' | '_') {
+          c
+        } else {
+          '_'
+        }
+      })
+      .collect();
+    let name = std::iter::once(base.clone())
+      .chain((1..).map(|attempt| format!("{base}{attempt}")))
+      .find(|name| !self.taken.contains(name))
+      .unwrap();
+    self.taken.insert(name.clone());
+    name
+  }
+}
+
+#[test]
+fn test_bundle() {
+  let dir = std::env::temp_dir().join(format!("verge-bundle-{}", std::process::id()));
+  let _ = std::fs::remove_dir_all(&dir);
+  std::fs::create_dir_all(dir.join("paths")).unwrap();
+  std::fs::write(
+    dir.join("openapi.json"),
+    r##"{
+      "openapi": "3.0.1",
+      "info": { "title": "test", "version": "1" },
+      "paths": {
+        "/users": { "$ref": "./paths/users.json#/users" },
+        "/groups": { "get": { "responses": {
+          "200": { "$ref": "#/components/responses/ok" }
+        } } }
+      },
+      "components": { "responses": { "ok": { "description": "ok" } } }
+    }"##,
+  )
+  .unwrap();
+  std::fs::write(
+    dir.join("paths/users.json"),
+    r##"{
+      "users": {
+        "get": { "responses": { "200": { "$ref": "#/ok" } } },
+        "post": { "operationId": "create_user", "responses": {} }
+      },
+      "ok": { "description": "the users" },
+      "loop": { "$ref": "#/loop" }
+    }"##,
+  )
+  .unwrap();
+
+  let spec = crate::input::read_bundled_spec(&dir.join("openapi.json"), false).unwrap();
+  let users = spec.paths.paths["/users"].as_item().unwrap();
+  assert!(users.get.is_some());
+  assert_eq!(
+    users.post.as_ref().unwrap().operation_id.as_deref(),
+    Some("create_user")
+  );
+  let users = serde_json::to_value(users).unwrap();
+  assert_eq!(users["get"]["responses"]["200"]["description"], "the users");
+  // references within the spec itself are kept
+  let groups = serde_json::to_value(&spec.paths.paths["/groups"]).unwrap();
+  assert_eq!(
+    groups["get"]["responses"]["200"]["$ref"],
+    "#/components/responses/ok"
+  );
+
+  let mut value = serde_json::json!({ "$ref": "./paths/users.json#/loop" });
+  let err = bundle(&mut value, &dir.join("openapi.json"), false).unwrap_err();
+  assert!(err.to_string().starts_with("circular $ref"), "{err}");
+  let mut value = serde_json::json!({ "$ref": "./paths/groups.json#/groups" });
+  assert!(bundle(&mut value, &dir.join("openapi.json"), false).is_err());
+
+  std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn test_bundle_recursive_schema() {
+  let dir =
+    std::env::temp_dir().join(format!("verge-recursive-ref-{}", std::process::id()));
+  let _ = std::fs::remove_dir_all(&dir);
+  std::fs::create_dir_all(&dir).unwrap();
+  std::fs::write(
+    dir.join("schemas.json"),
+    r##"{
+      "Tree": {
+        "type": "object",
+        "properties": { "children": { "type": "array", "items": { "$ref": "#/Tree" } } }
+      }
+    }"##,
+  )
+  .unwrap();
+
+  let mut value = serde_json::json!({
+    "paths": { "/trees": { "get": { "responses": { "200": { "description": "ok",
+      "content": { "application/json": { "schema": { "$ref": "./schemas.json#/Tree" } } }
+    } } } } },
+    "components": { "schemas": { "Tree": { "type": "string" } } }
+  });
+  bundle(&mut value, &dir.join("openapi.json"), false).unwrap();
+  assert_eq!(
+    value.pointer("/paths/~1trees/get/responses/200/content/application~1json/schema"),
+    Some(&schema_ref("Tree1"))
+  );
+  // moved next to the schema it would have replaced
+  assert_eq!(value["components"]["schemas"]["Tree"]["type"], "string");
+  assert_eq!(
+    value["components"]["schemas"]["Tree1"]["properties"]["children"]["items"],
+    schema_ref("Tree1")
+  );
+
+  std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn test_bundle_yaml() {
+  let dir =
+    std::env::temp_dir().join(format!("verge-bundle-yaml-{}", std::process::id()));
+  let _ = std::fs::remove_dir_all(&dir);
+  std::fs::create_dir_all(&dir).unwrap();
+  std::fs::write(
+    dir.join("openapi.yaml"),
+    "openapi: 3.0.1\n\
+     info: { title: test, version: '1' }\n\
+     paths:\n  \
+       /users: { $ref: './users.yml#/users' }\n",
+  )
+  .unwrap();
+  std::fs::write(
+    dir.join("users.yml"),
+    "users:\n  get: { operationId: list_users, responses: {} }\n",
+  )
+  .unwrap();
+
+  let spec = crate::input::read_bundled_spec(&dir.join("openapi.yaml"), false).unwrap();
+  let users = spec.paths.paths["/users"].as_item().unwrap();
+  assert_eq!(
+    users.get.as_ref().unwrap().operation_id.as_deref(),
+    Some("list_users")
+  );
+
+  std::fs::remove_dir_all(&dir).unwrap();
+}
@@ -0,0 +1,845 @@
+use clap::parser::ValueSource;
+use clap::{ArgMatches, Args, Parser, Subcommand};
+
+use std::path::PathBuf;
+
+#[cfg(feature = "codegen")]
+use crate::codegen::{Interface, TagStyle};
+use crate::config::SettingsLayer;
+use crate::lint::Severity;
+use crate::mapping::MappingKey;
+use crate::opid::check_template;
+use crate::output::Format;
+use crate::settings::{
+  CollisionStrategy, IntraSegment, Layout, MethodCase, MethodSuffix, NamingStyle,
+  Settings,
+};
+use crate::warning::WarningFormat;
+
+/// Preprocess an OpenAPI spec so that every operation carries the
+/// operation ID progenitor requires.
+#[derive(Parser, Debug)]
+#[command(version, about)]
+pub struct Cli {
+  #[command(subcommand)]
+  pub command: Option<Command>,
+
+  /// Spec to read, either a file, an http(s):// URL to fetch it from, or
+  /// `-` for stdin
+  #[arg(
+    short,
+    long,
+    value_name = "FILE",
+    env = "VERGE_SPEC_IN",
+    default_value = "./generator/swagger/v4.json"
+  )]
+  pub input: PathBuf,
+
+  /// File to write the processed spec to, or `-` for stdout. It must not
+  /// exist yet, unless `--overwrite` is given.
+  #[arg(
+    short,
+    long,
+    value_name = "FILE",
+    env = "VERGE_SPEC_OUT",
+    default_value = "./generator/swagger/generated-opids.json"
+  )]
+  pub output: PathBuf,
+
+  /// Parse the input as JSON5, allowing comments and trailing commas.
+  /// The output is plain JSON.
+  #[arg(long)]
+  pub json5: bool,
+
+  /// Inline `$ref`s to other files, like `./paths/users.json#/users`,
+  /// resolved relative to the file holding them, for specs split across
+  /// several files
+  #[arg(long, conflicts_with = "merge")]
+  pub bundle: bool,
+
+  /// Read the input as newline-delimited JSON, one operation per line like
+  /// `{"path": "/users", "method": "get", "operation": {...}}`, and write
+  /// a spec assembled from them
+  #[arg(long, conflicts_with_all = ["merge", "bundle", "json5"])]
+  pub ndjson: bool,
+
+  /// Format of the spec read from stdin with `--input -`. By default, it is
+  /// JSON if it starts with `{` or `[`, and YAML otherwise.
+  #[arg(long, value_enum, value_name = "FORMAT")]
+  pub stdin_format: Option<Format>,
+
+  /// Format of the spec written to stdout with `--output -`. Output files
+  /// are YAML if they end in `.yaml` or `.yml`, and JSON otherwise.
+  #[arg(long, value_enum, value_name = "FORMAT", default_value_t = Format::Json)]
+  pub stdout_format: Format,
+
+  /// Fail if a path has no operations, since the generated client would
+  /// carry it as a dead path, or if the spec is of an OpenAPI version other
+  /// than 3.0, which may be misread
+  #[arg(long)]
+  pub strict: bool,
+
+  /// Exit with an error if any warning was printed, after writing the
+  /// output all the same, so warnings fail CI
+  #[arg(long)]
+  pub exit_code_on_warnings: bool,
+
+  /// Remove paths without operations from the spec
+  #[arg(long)]
+  pub prune_empty: bool,
+
+  /// Fail before generating operation IDs if a path has more segments than
+  /// this, which is mostly a bug of the spec
+  #[arg(long, value_name = "DEPTH")]
+  pub max_path_depth: Option<usize>,
+
+  /// Remove operations marked `x-internal: true` before generating operation
+  /// IDs, along with the paths left without operations
+  #[arg(long)]
+  pub exclude_internal: bool,
+
+  /// Replace output files that already exist
+  #[arg(long)]
+  pub overwrite: bool,
+
+  /// Keep running and regenerate the output whenever the input changes,
+  /// overwriting the output every time
+  #[arg(long, conflicts_with_all = ["merge", "split_by_tag"])]
+  pub watch: bool,
+
+  /// Write compact JSON instead of pretty printing it
+  #[arg(long)]
+  pub compact: bool,
+
+  /// Merge the paths and components of these specs into a single spec with
+  /// one operation ID namespace, instead of reading the input file
+  #[arg(long, value_name = "FILE", num_args = 1..)]
+  pub merge: Vec<PathBuf>,
+
+  /// Write one spec per tag into the output directory instead of a
+  /// single output file. Operation IDs are generated for every
+  /// sub-spec independently.
+  #[arg(long)]
+  pub split_by_tag: bool,
+
+  /// Process each of these specs on its own, in parallel, writing the
+  /// results into the output directory under their original file names
+  #[arg(
+    long,
+    value_name = "FILE",
+    num_args = 1..,
+    conflicts_with_all = ["merge", "split_by_tag", "watch", "append_mapping", "mapping_out"]
+  )]
+  pub batch: Vec<PathBuf>,
+
+  /// Process every JSON spec below this directory on its own, writing the
+  /// results next to them as `*.opids.json`, or to the same place below
+  /// the output directory if one is given. Other JSON files are skipped.
+  #[arg(
+    long,
+    value_name = "DIR",
+    conflicts_with_all = ["batch", "merge", "split_by_tag", "watch", "append_mapping", "mapping_out"]
+  )]
+  pub recursive: Option<PathBuf>,
+
+  /// Output directory for modes that write more than one spec
+  #[arg(long, value_name = "DIR")]
+  pub out_dir: Option<PathBuf>,
+
+  /// Take the operation IDs of endpoints lacking one from this mapping
+  /// file, generate them only for endpoints it does not list yet, and
+  /// append those to the file. Existing entries keep their order.
+  #[arg(long, value_name = "FILE", conflicts_with = "split_by_tag")]
+  pub append_mapping: Option<PathBuf>,
+
+  /// Regenerate the operation IDs of the paths listed in this file, one per
+  /// line, instead of taking them from the mapping file. All other
+  /// endpoints keep the operation IDs the mapping has for them.
+  #[arg(long, value_name = "FILE", requires = "append_mapping")]
+  pub only_paths: Option<PathBuf>,
+
+  /// Write the synthetic operation IDs to this mapping file, replacing it
+  #[arg(long, value_name = "FILE", conflicts_with_all = ["split_by_tag", "append_mapping"])]
+  pub mapping_out: Option<PathBuf>,
+
+  /// Also write a copy of the output spec trimmed down to the operations
+  /// that got synthetic operation IDs, for reviewing the generated names
+  #[arg(long, value_name = "FILE", conflicts_with = "split_by_tag")]
+  pub synthetic_only_out: Option<PathBuf>,
+
+  /// Write all operation IDs to this file as CSV, with their method, path
+  /// and origin, replacing it
+  #[arg(long, value_name = "FILE", conflicts_with = "split_by_tag")]
+  pub csv_out: Option<PathBuf>,
+
+  /// Write a TypeScript enum `OperationId` of all operation IDs to this
+  /// file, replacing it, for frontends to refer to the same operations
+  #[arg(long, value_name = "FILE", conflicts_with = "split_by_tag")]
+  pub enum_out: Option<PathBuf>,
+
+  /// Write a Rust enum `OperationId` of all operation IDs to this file,
+  /// replacing it, whose `as_str` method returns the operation ID of a
+  /// variant, for client code to refer to operations type-safely
+  #[arg(long, value_name = "FILE", conflicts_with = "split_by_tag")]
+  pub rust_enum_out: Option<PathBuf>,
+
+  /// What to sort the entries of the `--mapping-out` file by, so it only
+  /// changes when operation IDs do
+  #[arg(long, value_enum, value_name = "KEY", default_value_t = MappingKey::Path)]
+  pub mapping_key: MappingKey,
+
+  /// Only check that every endpoint of this mapping file, as written by
+  /// `--mapping-out`, keeps its operation ID, and fail listing the ones
+  /// that would change. Nothing is written.
+  #[arg(
+    long,
+    value_name = "FILE",
+    conflicts_with_all = ["watch", "batch", "recursive", "split_by_tag", "append_mapping", "mapping_out"]
+  )]
+  pub check_stability: Option<PathBuf>,
+
+  /// Only print the first N operation IDs that would be generated, in the
+  /// order they are generated, and exit. Nothing is written.
+  #[arg(
+    long,
+    value_name = "N",
+    conflicts_with_all = ["watch", "batch", "recursive", "split_by_tag", "check_stability"]
+  )]
+  pub preview: Option<usize>,
+
+  /// Leave the spec untouched and only write the operation IDs to the
+  /// mapping file. No output spec is written.
+  #[arg(long, requires = "mapping_out")]
+  pub mapping_only: bool,
+
+  /// Remove the operations of the `--skip-methods` from the output spec
+  #[arg(long)]
+  pub drop_skipped_methods: bool,
+
+  /// Read settings from this TOML config file, with the keys
+  /// `--print-config` prints. Can be given multiple times, later files
+  /// override earlier ones, and flags given on the command line override
+  /// them all. Applies to the `diff` and `explain` commands, too.
+  #[arg(long, value_name = "FILE", global = true)]
+  pub config: Vec<PathBuf>,
+
+  /// Print the settings in effect as JSON to stderr and exit, without
+  /// processing anything
+  #[arg(long)]
+  pub print_config: bool,
+
+  /// Print the JSON Schema of config files and exit
+  #[arg(long, hide = true)]
+  pub dump_schema: bool,
+
+  /// Print warnings as GitHub Actions annotations of the input spec, so
+  /// they show up on pull requests
+  #[arg(long)]
+  pub github_annotations: bool,
+
+  #[command(flatten)]
+  pub settings: SettingsArgs,
+
+  #[cfg(feature = "codegen")]
+  #[command(flatten)]
+  pub codegen: CodegenArgs,
+
+  /// Print a summary of how the operation IDs were assigned to stderr
+  #[arg(long)]
+  pub stats: bool,
+
+  /// Write the summary of how the operation IDs were assigned to this file
+  /// as JSON, replacing it
+  #[arg(long, value_name = "FILE", conflicts_with_all = ["batch", "recursive", "split_by_tag"])]
+  pub stats_out: Option<PathBuf>,
+
+  /// Print the number of operations per tag to stderr. Operations without
+  /// tags are counted as `(untagged)`.
+  #[arg(long)]
+  pub tag_stats: bool,
+
+  /// Print the synthetic operation IDs, one endpoint per line
+  #[arg(long)]
+  pub report: bool,
+
+  /// Print the internal operation ID maps to stderr, for debugging
+  #[arg(long)]
+  pub dump: bool,
+
+  /// Print the synthetic operation IDs grouped by path
+  #[arg(long)]
+  pub report_grouped: bool,
+
+  /// Check that the generator works by assigning operation IDs to a small
+  /// built-in spec, printing PASS or FAIL. No files are read or written.
+  #[arg(long)]
+  pub selftest: bool,
+
+  /// Print the casing styles the explicit operation IDs are written in,
+  /// like camelCase or snake_case, with how many use each
+  #[arg(long)]
+  pub report_casing_mix: bool,
+
+  /// Print a histogram of the lengths of the synthetic operation IDs to
+  /// stderr, for picking a `--warn-id-length`
+  #[arg(long)]
+  pub operation_id_length_histogram: bool,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum Command {
+  /// Compare the operation IDs of two specs, listing the ones that were
+  /// added, removed or moved to a different path or method
+  Diff(DiffArgs),
+
+  /// Look up the endpoint an operation ID belongs to, and whether it was
+  /// generated
+  Explain(ExplainArgs),
+
+  /// Check the explicit operation IDs of a spec for consistency: invalid
+  /// identifiers, near-duplicates, mixed casing and overly long names.
+  /// Fails if any issue is at least as severe as `--fail-on`.
+  Lint(LintArgs),
+
+  /// Canonicalize the paths of a spec: collapse duplicate slashes, strip
+  /// trailing slashes and sort them. Operation IDs are not touched.
+  Normalize(NormalizeArgs),
+
+  /// Check that a mapping file covers every operation of a spec lacking an
+  /// operation ID, and has no entries for operations that are gone
+  ValidateMapping(ValidateMappingArgs),
+}
+
+/// Options for generating the client with progenitor
+#[cfg(feature = "codegen")]
+#[derive(Args, Debug)]
+pub struct CodegenArgs {
+  /// Also generate the Rust client from the processed spec with
+  /// progenitor, and write it to this file
+  #[arg(long, value_name = "FILE", conflicts_with = "mapping_only")]
+  pub codegen_out: Option<PathBuf>,
+
+  /// How the generated client takes the arguments of operations
+  #[arg(long, value_enum, value_name = "STYLE", default_value_t = Interface::Positional)]
+  pub interface: Interface,
+
+  /// Whether the methods of tagged operations go on the client or into a
+  /// trait per tag
+  #[arg(long, value_enum, value_name = "STYLE", default_value_t = TagStyle::Merged)]
+  pub tag_style: TagStyle,
+
+  /// Additional derive for the generated types, like
+  /// `schemars::JsonSchema`. Can be given multiple times.
+  #[arg(long, value_name = "PATH")]
+  pub derive: Vec<String>,
+}
+
+#[derive(Args, Debug)]
+pub struct DiffArgs {
+  /// Spec before the change
+  pub old: PathBuf,
+
+  /// Spec after the change
+  pub new: PathBuf,
+
+  /// Parse both specs as JSON5
+  #[arg(long)]
+  pub json5: bool,
+
+  #[command(flatten)]
+  pub settings: SettingsArgs,
+}
+
+#[derive(Args, Debug)]
+pub struct ExplainArgs {
+  /// Spec to assign operation IDs for
+  pub spec: PathBuf,
+
+  /// Operation ID to look up
+  pub operation_id: String,
+
+  /// Parse the spec as JSON5
+  #[arg(long)]
+  pub json5: bool,
+
+  #[command(flatten)]
+  pub settings: SettingsArgs,
+}
+
+#[derive(Args, Debug)]
+pub struct LintArgs {
+  /// Spec to check
+  pub spec: PathBuf,
+
+  /// Parse the spec as JSON5
+  #[arg(long)]
+  pub json5: bool,
+
+  /// Warn about operation IDs longer than this
+  #[arg(long, value_name = "LEN", default_value_t = 64)]
+  pub max_length: usize,
+
+  /// Fail if there are issues at least this severe
+  #[arg(long, value_enum, value_name = "SEVERITY", default_value_t = Severity::Error)]
+  pub fail_on: Severity,
+}
+
+#[derive(Args, Debug)]
+pub struct NormalizeArgs {
+  /// Spec to normalize
+  pub input: PathBuf,
+
+  /// File to write the normalized spec to
+  pub output: PathBuf,
+
+  /// Parse the spec as JSON5
+  #[arg(long)]
+  pub json5: bool,
+
+  /// Write compact JSON instead of pretty printing it
+  #[arg(long)]
+  pub compact: bool,
+
+  /// Replace the output file if it exists
+  #[arg(long)]
+  pub overwrite: bool,
+}
+
+#[derive(Args, Debug)]
+pub struct ValidateMappingArgs {
+  /// Spec the mapping belongs to
+  pub spec: PathBuf,
+
+  /// Mapping file to check
+  pub mapping: PathBuf,
+
+  /// Parse the spec as JSON5
+  #[arg(long)]
+  pub json5: bool,
+}
+
+/// Options controlling how operation IDs are assigned, shared by all
+/// commands generating them
+#[derive(Args, Debug)]
+pub struct SettingsArgs {
+  /// Treat paths that only differ in letter case as the same endpoint
+  #[arg(long)]
+  pub case_insensitive_paths: bool,
+
+  /// Treat paths that only differ in their Unicode normalization form as
+  /// the same endpoint, so such duplicates are reported
+  #[arg(long)]
+  pub normalize_unicode: bool,
+
+  /// Report all duplicate and conflicting operation IDs together, instead
+  /// of stopping at the first one
+  #[arg(long)]
+  pub collect_errors: bool,
+
+  /// Rewrite explicit operation IDs into the naming of synthetic ones, like
+  /// `get_users` for `getUsers`, so the whole client is named alike
+  #[arg(long)]
+  pub sanitize_explicit: bool,
+
+  /// Inline path items that are only a `$ref` into the spec, like
+  /// `#/x-path-items/users`, so their operations get operation IDs too.
+  /// Otherwise they are skipped with a warning.
+  #[arg(long)]
+  pub resolve_refs: bool,
+
+  /// Mark every operation with `x-operation-origin: explicit` or
+  /// `x-operation-origin: synthetic`, telling which operation IDs come from
+  /// the spec
+  #[arg(long)]
+  pub annotate_origin: bool,
+
+  /// Additional name synthetic operation IDs must not use, on top of the
+  /// helper names of progenitor's client. Can be given multiple times.
+  #[arg(long, value_name = "NAME")]
+  pub reserved_name: Vec<String>,
+
+  /// Operation ID to keep free for a method added to the client by hand.
+  /// Can be given multiple times.
+  #[arg(long, value_name = "ID")]
+  pub reserve_id: Vec<String>,
+
+  /// Leave the `_by_param` suffixes out of synthetic operation IDs, so
+  /// `GET /users/{id}` becomes `users_get` (or `users1_get` if `GET /users`
+  /// exists too)
+  #[arg(long)]
+  pub no_params_in_id: bool,
+
+  /// Only add the `_by_param` suffixes to synthetic operation IDs when the
+  /// name without them is taken, so `GET /users/{id}` becomes `users_get`
+  /// unless `GET /users` exists too
+  #[arg(long, conflicts_with = "no_params_in_id")]
+  pub minimal_suffix: bool,
+
+  /// Name synthetic operation IDs after the last segment of the path that
+  /// is not a parameter only, like `widgets_get` for `/api/v1/widgets`
+  #[arg(long)]
+  pub last_segment_only: bool,
+
+  /// How synthetic operation IDs that would collide are told apart: by a
+  /// number like `users1_get`, or, with `--last-segment-only`, by the
+  /// parent segments of the path like `b_users_get` (parent-segment)
+  #[arg(long, value_enum, value_name = "STRATEGY", default_value_t = CollisionStrategy::Number)]
+  pub collision_strategy: CollisionStrategy,
+
+  /// Where the method goes in synthetic operation IDs: after the path like
+  /// `foo_bar_get` (path-first), or uppercase in front like `GET__foo_bar`
+  /// (method-prefix)
+  #[arg(long, value_enum, value_name = "LAYOUT", default_value_t = Layout::PathFirst)]
+  pub layout: Layout,
+
+  /// What sets the method apart from the path with `--layout method-prefix`
+  #[arg(long, value_name = "SEPARATOR", default_value = "__")]
+  pub method_separator: String,
+
+  /// Put synthetic operation IDs of tagged operations into a namespace
+  /// named after their first tag, like `billing.invoices_get`
+  #[arg(long)]
+  pub prefix_by_tag: bool,
+
+  /// What joins the namespace and the rest of the operation ID with
+  /// `--prefix-by-tag`. It must not contain letters, digits, `_` or `-`, so
+  /// the two can be told apart again.
+  #[arg(
+    long,
+    value_name = "JOINER",
+    default_value = ".",
+    requires = "prefix_by_tag",
+    value_parser = parse_namespace_joiner
+  )]
+  pub namespace_joiner: String,
+
+  /// Word for synthetic operation IDs of paths without any words, like the
+  /// root, which becomes `root_get` by default
+  #[arg(long, value_name = "WORD", default_value = "root")]
+  pub root_name: String,
+
+  /// Mark path parameters catching all the rest of the path in synthetic
+  /// operation IDs, like `api_get_by_proxy_catchall` for `/api/{proxy+}`
+  #[arg(long, conflicts_with = "no_params_in_id")]
+  pub mark_catch_all: bool,
+
+  /// Spell out path parameters a path repeats by mistake only once in
+  /// synthetic operation IDs, like `sub_get_by_id` for `/{id}/sub/{id}`
+  #[arg(long, conflicts_with = "no_params_in_id")]
+  pub dedupe_params: bool,
+
+  /// Append a word for the content type of the request body to synthetic
+  /// operation IDs, like `files_post_multipart` for `multipart/form-data`,
+  /// if the body has a single one
+  #[arg(long)]
+  pub request_content_type: bool,
+
+  /// Name operations lacking an operation ID as the `x-operation-names`
+  /// extension of the spec's `info` says, like
+  /// `{ "GET /users": "list_users" }`
+  #[arg(long)]
+  pub info_operation_names: bool,
+
+  /// Only count the path parameters in synthetic operation IDs of paths
+  /// with more of them than this, like `users_get_by_6_params`
+  #[arg(long, value_name = "N", conflicts_with = "no_params_in_id")]
+  pub summarize_params_above: Option<usize>,
+
+  /// Percent-decode paths, and turn `+` into a space, before deriving
+  /// synthetic operation IDs from them
+  #[arg(long)]
+  pub decode_paths: bool,
+
+  /// Drop a file extension from the last path segment before deriving
+  /// synthetic operation IDs, like `report_get` for `/report.pdf`
+  #[arg(long)]
+  pub strip_extension: bool,
+
+  /// Prepend the path of the spec's first server URL to synthetic
+  /// operation IDs, like `v2_users_get` for `https://api.example.com/v2`
+  #[arg(long)]
+  pub base_path_in_id: bool,
+
+  /// Template to render synthetic operation IDs from, using the
+  /// placeholders {path}, {method}, {tag}, {summary}, {params} and
+  /// {attempt}, like `{tag}_{summary}`
+  #[arg(long, value_parser = parse_template)]
+  pub template: Option<String>,
+
+  /// Warn about synthetic operation IDs longer than this
+  #[arg(long, value_name = "LEN", default_value_t = 64)]
+  pub warn_id_length: usize,
+
+  /// When to end synthetic operation IDs in the method: always, never
+  /// (collisions are an error then), or only when other operations share
+  /// the path (auto)
+  #[arg(long, value_enum, value_name = "POLICY", default_value_t = MethodSuffix::Always)]
+  pub method_suffix: MethodSuffix,
+
+  /// Case of the method in synthetic operation IDs, like `users_GET` for
+  /// upper. Templates are always lowercased, so it cannot be combined with
+  /// one.
+  #[arg(
+    long,
+    value_enum,
+    value_name = "CASE",
+    default_value_t = MethodCase::Lower,
+    conflicts_with = "template"
+  )]
+  pub method_case: MethodCase,
+
+  /// How to join the words of synthetic operation IDs: snake_case,
+  /// kebab-case, camelCase or PascalCase for generators of other languages
+  #[arg(long, value_enum, value_name = "STYLE", default_value_t = NamingStyle::Snake)]
+  pub naming_style: NamingStyle,
+
+  /// Whether punctuation within a path segment, like the `-` of
+  /// `/user-profiles`, separates words or is dropped
+  #[arg(long, value_enum, value_name = "MODE", default_value_t = IntraSegment::Split)]
+  pub intra_segment: IntraSegment,
+
+  /// Methods to assign no operation IDs to, like `options,head`
+  #[arg(long, value_name = "METHOD", value_delimiter = ',')]
+  pub skip_methods: Vec<String>,
+
+  /// Fail if an operation with this tag lacks an explicit operation ID,
+  /// rather than generating one. Can be given multiple times.
+  #[arg(long, value_name = "TAG")]
+  pub deny_synthetic_for_tag: Vec<String>,
+
+  /// Acronym that is a word of its own in camelCase and PascalCase
+  /// operation IDs, like `HTTP` for `/XMLHTTPRequest`. Can be given
+  /// multiple times.
+  #[arg(long, value_name = "ACRONYM")]
+  pub acronym: Vec<String>,
+
+  /// Spell out digits in synthetic operation IDs, like `v_two_items_get`
+  /// for `/v2/items`
+  #[arg(long)]
+  pub spell_out_digits: bool,
+
+  /// Word to use for a method in synthetic operation IDs, like `get=fetch`
+  /// for `users_fetch`. Can be given multiple times.
+  #[arg(long, value_name = "METHOD=WORD", value_parser = parse_method_alias)]
+  pub method_alias: Vec<(String, String)>,
+
+  /// Spelling of a word of operation IDs, like `oauth=OAuth` for
+  /// `OAuth_token`, for brand names lowercasing would ruin. Can be given
+  /// multiple times.
+  #[arg(long, value_name = "WORD=SPELLING", value_parser = parse_word_exception)]
+  pub word_exception: Vec<(String, String)>,
+}
+
+fn parse_template(template: &str) -> Result<String, String> {
+  check_template(template).map_err(|err| err.to_string())?;
+  Ok(template.to_string())
+}
+
+/// The alias of `method` as [`Settings::method_aliases`] keeps it, if
+/// neither is empty
+pub fn method_alias(method: &str, word: &str) -> Option<(String, String)> {
+  (!method.is_empty() && !word.is_empty())
+    .then(|| (method.to_lowercase(), word.to_string()))
+}
+
+fn parse_method_alias(alias: &str) -> Result<(String, String), String> {
+  alias
+    .split_once('=')
+    .and_then(|(method, word)| method_alias(method, word))
+    .ok_or_else(|| format!("expected METHOD=WORD, got {alias:?}"))
+}
+
+pub fn parse_namespace_joiner(joiner: &str) -> Result<String, String> {
+  if joiner.is_empty()
+    || joiner.contains(|c: char| c.is_alphanumeric() || c == '_' || c == '-')
+  {
+    return Err(format!(
+      "{joiner:?} cannot be told apart from the words of operation ids"
+    ));
+  }
+  Ok(joiner.to_string())
+}
+
+/// The exception for `word` as [`Settings::word_exceptions`] keeps it, if
+/// `word` is a single word and `spelling` is not empty
+pub fn word_exception(word: &str, spelling: &str) -> Option<(String, String)> {
+  (!word.is_empty() && word.chars().all(char::is_alphanumeric) && !spelling.is_empty())
+    .then(|| (word.to_lowercase(), spelling.to_string()))
+}
+
+fn parse_word_exception(exception: &str) -> Result<(String, String), String> {
+  exception
+    .split_once('=')
+    .and_then(|(word, spelling)| word_exception(word, spelling))
+    .ok_or_else(|| format!("expected WORD=SPELLING, got {exception:?}"))
+}
+
+impl Cli {
+  /// How warnings are printed
+  pub fn warning_format(&self) -> WarningFormat {
+    if self.github_annotations {
+      WarningFormat::GitHub
+    } else {
+      WarningFormat::Plain
+    }
+  }
+}
+
+impl SettingsArgs {
+  /// The [`Settings`] set by flags given on the command line, out of
+  /// `matches` these arguments were parsed from. Flags left at their
+  /// default do not override config files.
+  pub fn layer(&self, matches: &ArgMatches) -> SettingsLayer {
+    let given = |id: &str| {
+      matches
+        .value_source(id)
+        .is_some_and(|source| source != ValueSource::DefaultValue)
+    };
+    let settings = self.settings();
+    SettingsLayer {
+      case_insensitive_paths: given("case_insensitive_paths")
+        .then_some(settings.case_insensitive_paths),
+      normalize_unicode: given("normalize_unicode").then_some(settings.normalize_unicode),
+      reserved_names: given("reserved_name").then_some(settings.reserved_names),
+      reserved_operation_ids: given("reserve_id")
+        .then_some(settings.reserved_operation_ids),
+      params_in_id: given("no_params_in_id").then_some(settings.params_in_id),
+      minimal_suffix: given("minimal_suffix").then_some(settings.minimal_suffix),
+      summarize_params_above: given("summarize_params_above")
+        .then_some(settings.summarize_params_above),
+      skip_methods: given("skip_methods").then_some(settings.skip_methods),
+      deny_synthetic_tags: given("deny_synthetic_for_tag")
+        .then_some(settings.deny_synthetic_tags),
+      template: given("template").then_some(settings.template),
+      last_segment_only: given("last_segment_only").then_some(settings.last_segment_only),
+      tag_namespace: given("prefix_by_tag").then_some(settings.tag_namespace),
+      mark_catch_all: given("mark_catch_all").then_some(settings.mark_catch_all),
+      dedupe_params: given("dedupe_params").then_some(settings.dedupe_params),
+      request_content_type: given("request_content_type")
+        .then_some(settings.request_content_type),
+      info_operation_names: given("info_operation_names")
+        .then_some(settings.info_operation_names),
+      collision_strategy: given("collision_strategy")
+        .then_some(settings.collision_strategy),
+      layout: given("layout").then_some(settings.layout),
+      method_separator: given("method_separator").then_some(settings.method_separator),
+      root_name: given("root_name").then_some(settings.root_name),
+      decode_paths: given("decode_paths").then_some(settings.decode_paths),
+      strip_extension: given("strip_extension").then_some(settings.strip_extension),
+      base_path_in_id: given("base_path_in_id").then_some(settings.base_path_in_id),
+      warn_id_length: given("warn_id_length").then_some(settings.warn_id_length),
+      method_aliases: given("method_alias").then_some(settings.method_aliases),
+      word_exceptions: given("word_exception").then_some(settings.word_exceptions),
+      method_suffix: given("method_suffix").then_some(settings.method_suffix),
+      method_case: given("method_case").then_some(settings.method_case),
+      naming_style: given("naming_style").then_some(settings.naming_style),
+      intra_segment: given("intra_segment").then_some(settings.intra_segment),
+      acronyms: given("acronym").then_some(settings.acronyms),
+      spell_out_digits: given("spell_out_digits").then_some(settings.spell_out_digits),
+      sanitize_explicit: given("sanitize_explicit").then_some(settings.sanitize_explicit),
+      annotate_origin: given("annotate_origin").then_some(settings.annotate_origin),
+      resolve_refs: given("resolve_refs").then_some(settings.resolve_refs),
+      collect_errors: given("collect_errors").then_some(settings.collect_errors),
+    }
+  }
+
+  /// [`Settings`] selected by the command line
+  pub fn settings(&self) -> Settings {
+    let mut settings = Settings {
+      case_insensitive_paths: self.case_insensitive_paths,
+      normalize_unicode: self.normalize_unicode,
+      collect_errors: self.collect_errors,
+      annotate_origin: self.annotate_origin,
+      resolve_refs: self.resolve_refs,
+      sanitize_explicit: self.sanitize_explicit,
+      reserved_operation_ids: self.reserve_id.clone(),
+      params_in_id: !self.no_params_in_id,
+      minimal_suffix: self.minimal_suffix,
+      summarize_params_above: self.summarize_params_above,
+      template: self.template.clone(),
+      last_segment_only: self.last_segment_only,
+      collision_strategy: self.collision_strategy,
+      layout: self.layout,
+      method_separator: self.method_separator.clone(),
+      mark_catch_all: self.mark_catch_all,
+      dedupe_params: self.dedupe_params,
+      request_content_type: self.request_content_type,
+      info_operation_names: self.info_operation_names,
+      tag_namespace: self.prefix_by_tag.then(|| self.namespace_joiner.clone()),
+      root_name: self.root_name.clone(),
+      decode_paths: self.decode_paths,
+      strip_extension: self.strip_extension,
+      base_path_in_id: self.base_path_in_id,
+      warn_id_length: self.warn_id_length,
+      method_aliases: self.method_alias.iter().cloned().collect(),
+      word_exceptions: self.word_exception.iter().cloned().collect(),
+      method_suffix: self.method_suffix,
+      method_case: self.method_case,
+      naming_style: self.naming_style,
+      intra_segment: self.intra_segment,
+      acronyms: self.acronym.clone(),
+      skip_methods: self
+        .skip_methods
+        .iter()
+        .map(|method| method.to_lowercase())
+        .collect(),
+      deny_synthetic_tags: self.deny_synthetic_for_tag.iter().cloned().collect(),
+      spell_out_digits: self.spell_out_digits,
+      ..Settings::default()
+    };
+    settings
+      .reserved_names
+      .extend(self.reserved_name.iter().cloned());
+    settings
+  }
+}
+
+#[test]
+fn test_cli() {
+  use clap::CommandFactory;
+  Cli::command().debug_assert();
+}
+
+#[test]
+fn test_print_config() {
+  let cli = Cli::parse_from(["verge", "--print-config", "--naming-style", "kebab"]);
+  let config = serde_json::to_value(cli.settings.settings()).unwrap();
+  assert_eq!(config["naming_style"], "kebab");
+  assert_eq!(config["method_suffix"], "always");
+  assert_eq!(config["params_in_id"], true);
+}
+
+#[test]
+fn test_settings_layer() {
+  use clap::{CommandFactory, FromArgMatches};
+
+  let matches = Cli::command().get_matches_from(["verge", "--naming-style", "kebab"]);
+  let cli = Cli::from_arg_matches(&matches).unwrap();
+  let layer = cli.settings.layer(&matches);
+  assert_eq!(layer.naming_style, Some(NamingStyle::Kebab));
+  // flags left at their default leave config files alone
+  assert_eq!(layer.method_suffix, None);
+  assert_eq!(layer.params_in_id, None);
+
+  let mut settings = Settings::default();
+  crate::config::parse_layer("naming_style = \"camel\"\nroot_name = \"home\"", "test")
+    .unwrap()
+    .merge(layer)
+    .apply(&mut settings);
+  assert_eq!(settings.naming_style, NamingStyle::Kebab);
+  assert_eq!(settings.root_name, "home");
+}
+
+#[test]
+fn test_config_for_commands() {
+  let cli = Cli::parse_from([
+    "verge",
+    "explain",
+    "spec.json",
+    "users_get",
+    "--config",
+    "a.toml",
+  ]);
+  assert_eq!(cli.config, [PathBuf::from("a.toml")]);
+  assert!(matches!(cli.command, Some(Command::Explain(_))));
+}
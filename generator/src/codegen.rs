@@ -0,0 +1,89 @@
+//! Generating the client with progenitor right away, instead of writing the
+//! spec for `cargo progenitor` to pick up.
+
+use anyhow::{anyhow, Result};
+use openapiv3::OpenAPI;
+
+use std::path::Path;
+
+use crate::cli::CodegenArgs;
+use crate::output;
+
+/// How progenitor passes arguments to the methods of the client
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum Interface {
+  /// Every argument is a method argument
+  #[default]
+  Positional,
+  /// Methods return a builder taking the arguments
+  Builder,
+}
+
+/// How progenitor organizes the methods of operations with tags
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum TagStyle {
+  /// All methods are on the client
+  #[default]
+  Merged,
+  /// Every tag gets a trait of its own
+  Separate,
+}
+
+/// Generate the Rust client for `spec`, whose operations must all carry an
+/// operation ID by now
+pub fn generate_client(spec: &OpenAPI, args: &CodegenArgs) -> Result<String> {
+  // progenitor is built on the upstream openapiv3 crate, which shares the
+  // serialized form, but not the types, with the fork used here
+  let spec: upstream_openapiv3::OpenAPI =
+    serde_json::from_value(serde_json::to_value(spec)?)?;
+
+  let mut settings = progenitor::GenerationSettings::default();
+  settings
+    .with_interface(match args.interface {
+      Interface::Positional => progenitor::InterfaceStyle::Positional,
+      Interface::Builder => progenitor::InterfaceStyle::Builder,
+    })
+    .with_tag(match args.tag_style {
+      TagStyle::Merged => progenitor::TagStyle::Merged,
+      TagStyle::Separate => progenitor::TagStyle::Separate,
+    });
+  args.derive.iter().for_each(|derive| {
+    settings.with_derive(derive.clone());
+  });
+
+  let tokens = progenitor::Generator::new(&settings)
+    .generate_tokens(&spec)
+    .map_err(|err| anyhow!("generating the client failed: {err}"))?;
+  let file = syn::parse2(tokens)?;
+  Ok(prettyplease::unparse(&file))
+}
+
+/// Generate the Rust client for `spec` and write it to `path`, replacing
+/// the file if it exists
+pub fn write_client(spec: &OpenAPI, args: &CodegenArgs, path: &Path) -> Result<()> {
+  let code = generate_client(spec, args)?;
+  output::write_output(path, true, |writer| Ok(writer.write_all(code.as_bytes())?))
+}
+
+#[test]
+fn test_generate_client() {
+  use clap::Parser;
+
+  #[derive(Parser)]
+  struct Args {
+    #[command(flatten)]
+    codegen: CodegenArgs,
+  }
+
+  let mut spec = crate::spec::mk_spec(
+    r#"{
+      "/users": { "get": { "responses": { "204": { "description": "ok" } } } }
+    }"#,
+  );
+  crate::generate::gen_operation_ids(&mut spec, &Default::default()).unwrap();
+
+  let args = Args::parse_from(["test", "--interface", "builder"]).codegen;
+  let code = generate_client(&spec, &args).unwrap();
+  assert!(code.contains("pub struct Client"), "{code}");
+  assert!(code.contains("fn users_get"), "{code}");
+}
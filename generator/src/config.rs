@@ -0,0 +1,315 @@
+//! Config files holding settings, so shared settings do not need to be
+//! repeated on every command line. They are TOML files with the keys
+//! `--print-config` prints, like `naming_style = "kebab"`.
+
+use anyhow::{anyhow, Result};
+use clap::ArgMatches;
+use schemars::JsonSchema;
+use serde::Deserialize;
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::PathBuf;
+
+use crate::cli::{method_alias, parse_namespace_joiner, word_exception, SettingsArgs};
+use crate::opid::check_template;
+use crate::settings::{
+  CollisionStrategy, IntraSegment, Layout, MethodCase, MethodSuffix, NamingStyle,
+  Settings,
+};
+
+/// Declare [`SettingsLayer`] with a field for each of the given fields of
+/// [`Settings`], so merging and applying layers cannot miss one
+macro_rules! settings_layer {
+  ($($field:ident: $ty:ty,)*) => {
+    /// Some of the [`Settings`], as set by a config file or the command
+    /// line. Fields that are [`None`] are left as the layers below have them.
+    #[derive(Debug, Default, PartialEq, Deserialize, JsonSchema)]
+    #[serde(deny_unknown_fields)]
+    pub struct SettingsLayer {
+      $(pub $field: Option<$ty>,)*
+    }
+
+    impl SettingsLayer {
+      /// This layer with the fields `over` sets replaced
+      pub fn merge(self, over: SettingsLayer) -> SettingsLayer {
+        SettingsLayer {
+          $($field: over.$field.or(self.$field),)*
+        }
+      }
+
+      /// Set the fields of `settings` this layer sets
+      pub fn apply(self, settings: &mut Settings) {
+        $(if let Some(value) = self.$field {
+          settings.$field = value;
+        })*
+      }
+    }
+  };
+}
+
+// `write_back` is left out, it follows from the command
+settings_layer! {
+  case_insensitive_paths: bool,
+  normalize_unicode: bool,
+  reserved_names: BTreeSet<String>,
+  reserved_operation_ids: Vec<String>,
+  params_in_id: bool,
+  minimal_suffix: bool,
+  summarize_params_above: Option<usize>,
+  skip_methods: BTreeSet<String>,
+  deny_synthetic_tags: BTreeSet<String>,
+  template: Option<String>,
+  last_segment_only: bool,
+  tag_namespace: Option<String>,
+  mark_catch_all: bool,
+  dedupe_params: bool,
+  request_content_type: bool,
+  info_operation_names: bool,
+  collision_strategy: CollisionStrategy,
+  layout: Layout,
+  method_separator: String,
+  root_name: String,
+  decode_paths: bool,
+  strip_extension: bool,
+  base_path_in_id: bool,
+  warn_id_length: usize,
+  method_aliases: BTreeMap<String, String>,
+  word_exceptions: BTreeMap<String, String>,
+  method_suffix: MethodSuffix,
+  method_case: MethodCase,
+  naming_style: NamingStyle,
+  intra_segment: IntraSegment,
+  acronyms: Vec<String>,
+  spell_out_digits: bool,
+  sanitize_explicit: bool,
+  annotate_origin: bool,
+  resolve_refs: bool,
+  collect_errors: bool,
+}
+
+impl SettingsLayer {
+  /// Reject settings that cannot be combined, like the command line does,
+  /// so a config file cannot combine them either. Meant for the layer
+  /// merged from all config files and the command line, as the settings may
+  /// come from different ones.
+  pub fn check_conflicts(&self) -> Result<()> {
+    let without_params = self.params_in_id == Some(false);
+    let conflicts = [
+      (
+        "method_case",
+        "template",
+        self
+          .method_case
+          .is_some_and(|case| case != MethodCase::Lower)
+          && matches!(self.template, Some(Some(_))),
+      ),
+      (
+        "minimal_suffix",
+        "params_in_id = false",
+        without_params && self.minimal_suffix == Some(true),
+      ),
+      (
+        "mark_catch_all",
+        "params_in_id = false",
+        without_params && self.mark_catch_all == Some(true),
+      ),
+      (
+        "dedupe_params",
+        "params_in_id = false",
+        without_params && self.dedupe_params == Some(true),
+      ),
+      (
+        "summarize_params_above",
+        "params_in_id = false",
+        without_params && matches!(self.summarize_params_above, Some(Some(_))),
+      ),
+    ];
+    match conflicts.iter().find(|(_, _, conflicting)| *conflicting) {
+      Some((setting, other, _)) => Err(anyhow!(
+        "conflicting settings: {setting} cannot be combined with {other}"
+      )),
+      None => Ok(()),
+    }
+  }
+}
+
+/// Parse the config file `content`, read from `name`. Values are checked
+/// and normalized like the command line does, so methods and words are
+/// lowercased, for instance.
+pub fn parse_layer(content: &str, name: &str) -> Result<SettingsLayer> {
+  let invalid = |err: String| anyhow!("invalid config file {name}: {err}");
+  let mut layer: SettingsLayer =
+    toml::from_str(content).map_err(|err| invalid(err.to_string()))?;
+  if let Some(Some(template)) = &layer.template {
+    check_template(template).map_err(|err| invalid(err.to_string()))?;
+  }
+  if let Some(Some(joiner)) = &layer.tag_namespace {
+    parse_namespace_joiner(joiner).map_err(invalid)?;
+  }
+  if let Some(aliases) = layer.method_aliases.take() {
+    let aliases = aliases
+      .iter()
+      .map(|(method, word)| {
+        method_alias(method, word)
+          .ok_or_else(|| invalid(format!("invalid method alias {method:?} = {word:?}")))
+      })
+      .collect::<Result<_>>()?;
+    layer.method_aliases = Some(aliases);
+  }
+  if let Some(exceptions) = layer.word_exceptions.take() {
+    let exceptions = exceptions
+      .iter()
+      .map(|(word, spelling)| {
+        word_exception(word, spelling).ok_or_else(|| {
+          invalid(format!("invalid word exception {word:?} = {spelling:?}"))
+        })
+      })
+      .collect::<Result<_>>()?;
+    layer.word_exceptions = Some(exceptions);
+  }
+  if let Some(methods) = layer.skip_methods.take() {
+    layer.skip_methods =
+      Some(methods.iter().map(|method| method.to_lowercase()).collect());
+  }
+  Ok(layer)
+}
+
+/// The settings of the config files at `paths`, in order, overridden by the
+/// flags `args` were parsed from out of `matches`. Settings that cannot be
+/// combined are rejected, wherever they come from.
+pub fn layered_settings(
+  paths: &[PathBuf],
+  args: &SettingsArgs,
+  matches: &ArgMatches,
+) -> Result<Settings> {
+  let layer = read_layers(paths)?.merge(args.layer(matches));
+  layer.check_conflicts()?;
+  let mut settings = Settings::default();
+  layer.apply(&mut settings);
+  Ok(settings)
+}
+
+/// JSON Schema of config files, for editors to check and complete them with
+pub fn schema() -> schemars::schema::RootSchema {
+  schemars::schema_for!(SettingsLayer)
+}
+
+/// Read the config files at `paths` and merge them in order, so later files
+/// override earlier ones field by field
+pub fn read_layers(paths: &[PathBuf]) -> Result<SettingsLayer> {
+  paths
+    .iter()
+    .try_fold(SettingsLayer::default(), |layers, path| {
+      let content = std::fs::read_to_string(path)
+        .map_err(|err| anyhow!("cannot read config file {}: {err}", path.display()))?;
+      Ok(layers.merge(parse_layer(&content, &path.display().to_string())?))
+    })
+}
+
+#[test]
+fn test_layered_config() {
+  let base = parse_layer(
+    r#"
+      naming_style = "kebab"
+      root_name = "home"
+      method_aliases = { get = "fetch" }
+    "#,
+    "base.toml",
+  )
+  .unwrap();
+  let project = parse_layer(r#"naming_style = "camel""#, "project.toml").unwrap();
+
+  let mut settings = Settings::default();
+  base.merge(project).apply(&mut settings);
+  assert_eq!(settings.naming_style, NamingStyle::Camel);
+  assert_eq!(settings.root_name, "home");
+  assert_eq!(settings.method_aliases["get"], "fetch");
+  assert_eq!(settings.warn_id_length, Settings::default().warn_id_length);
+
+  assert!(parse_layer("naming_styles = \"kebab\"", "typo.toml").is_err());
+  assert!(parse_layer("template = \"{nope}\"", "template.toml").is_err());
+}
+
+#[test]
+fn test_normalized_layer() {
+  let mut settings = Settings::default();
+  parse_layer(
+    r#"
+      method_aliases = { GET = "fetch" }
+      word_exceptions = { OAuth = "OAuth" }
+      skip_methods = ["HEAD"]
+    "#,
+    "case.toml",
+  )
+  .unwrap()
+  .apply(&mut settings);
+  assert_eq!(settings.method_aliases["get"], "fetch");
+  assert_eq!(settings.word_exceptions["oauth"], "OAuth");
+  assert!(settings.skip_methods.contains("head"));
+}
+
+#[test]
+fn test_invalid_layer_values() {
+  let err = |content: &str| parse_layer(content, "bad.toml").unwrap_err().to_string();
+  assert_eq!(
+    err(r#"method_aliases = { get = "" }"#),
+    "invalid config file bad.toml: invalid method alias \"get\" = \"\""
+  );
+  assert_eq!(
+    err(r#"word_exceptions = { "o auth" = "OAuth" }"#),
+    "invalid config file bad.toml: invalid word exception \"o auth\" = \"OAuth\""
+  );
+  assert_eq!(
+    err(r#"tag_namespace = "_""#),
+    "invalid config file bad.toml: \"_\" cannot be told apart from the words of \
+     operation ids"
+  );
+}
+
+#[test]
+fn test_check_conflicts() {
+  let template = parse_layer(r#"template = "{path}_{method}""#, "base.toml").unwrap();
+  let upper = parse_layer(r#"method_case = "upper""#, "project.toml").unwrap();
+  assert_eq!(
+    template
+      .merge(upper)
+      .check_conflicts()
+      .unwrap_err()
+      .to_string(),
+    "conflicting settings: method_case cannot be combined with template"
+  );
+
+  let layer =
+    parse_layer("params_in_id = false\nminimal_suffix = true", "params.toml").unwrap();
+  assert_eq!(
+    layer.check_conflicts().unwrap_err().to_string(),
+    "conflicting settings: minimal_suffix cannot be combined with params_in_id = false"
+  );
+
+  // the defaults of the other settings are fine
+  assert!(parse_layer(r#"method_case = "lower""#, "lower.toml")
+    .unwrap()
+    .merge(parse_layer(r#"template = "{path}""#, "template.toml").unwrap())
+    .check_conflicts()
+    .is_ok());
+  assert!(
+    parse_layer("params_in_id = false\nminimal_suffix = false", "off.toml")
+      .unwrap()
+      .check_conflicts()
+      .is_ok()
+  );
+}
+
+#[test]
+fn test_schema() {
+  let schema = serde_json::to_value(schema()).unwrap();
+  let properties = &schema["properties"];
+  assert!(properties["naming_style"].is_object(), "{schema}");
+  assert!(properties["method_suffix"].is_object(), "{schema}");
+  assert!(properties["reserved_names"].is_object(), "{schema}");
+  assert!(schema["definitions"]["NamingStyle"].is_object(), "{schema}");
+  // config files cannot set it
+  assert!(properties.get("write_back").is_none(), "{schema}");
+  // and need not set anything
+  assert!(schema.get("required").is_none(), "{schema}");
+}
@@ -0,0 +1,126 @@
+use anyhow::Result;
+
+use std::collections::BTreeSet;
+use std::fmt;
+
+use crate::cli::DiffArgs;
+use crate::generate::opids_for_file;
+use crate::opid::OperationIds;
+use crate::settings::Settings;
+
+/// Difference in a single operation ID between two specs
+#[derive(Debug, PartialEq, Eq)]
+pub enum Change {
+  /// The operation ID only exists in the new spec
+  Added {
+    operation_id: String,
+    endpoint: String,
+  },
+  /// The operation ID only exists in the old spec
+  Removed {
+    operation_id: String,
+    endpoint: String,
+  },
+  /// The operation ID exists in both specs, but names a different
+  /// path or method
+  Moved {
+    operation_id: String,
+    from: String,
+    to: String,
+  },
+}
+
+impl fmt::Display for Change {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      Change::Added {
+        operation_id,
+        endpoint,
+      } => write!(f, "added   {operation_id}: {endpoint}"),
+      Change::Removed {
+        operation_id,
+        endpoint,
+      } => write!(f, "removed {operation_id}: {endpoint}"),
+      Change::Moved {
+        operation_id,
+        from,
+        to,
+      } => write!(f, "moved   {operation_id}: {from} -> {to}"),
+    }
+  }
+}
+
+/// Compare two sets of operation IDs, returning the changes sorted by
+/// operation ID
+pub fn diff(old: &OperationIds, new: &OperationIds) -> Vec<Change> {
+  let old_opids: BTreeSet<&str> = old.iter().map(|(opid, _)| opid).collect();
+  let new_opids: BTreeSet<&str> = new.iter().map(|(opid, _)| opid).collect();
+
+  old_opids
+    .union(&new_opids)
+    .filter_map(|opid| {
+      let operation_id = opid.to_string();
+      match (old.path_method(opid), new.path_method(opid)) {
+        (Some(from), Some(to)) if from != to => Some(Change::Moved {
+          operation_id,
+          from: from.to_string(),
+          to: to.to_string(),
+        }),
+        (Some(_), Some(_)) | (None, None) => None,
+        (Some(from), None) => Some(Change::Removed {
+          operation_id,
+          endpoint: from.to_string(),
+        }),
+        (None, Some(to)) => Some(Change::Added {
+          operation_id,
+          endpoint: to.to_string(),
+        }),
+      }
+    })
+    .collect()
+}
+
+/// Run the `diff` command with `settings`, printing one line per change
+pub fn run(args: &DiffArgs, settings: &Settings) -> Result<()> {
+  let old = opids_for_file(&args.old, args.json5, settings)?;
+  let new = opids_for_file(&args.new, args.json5, settings)?;
+  diff(&old, &new)
+    .iter()
+    .for_each(|change| println!("{change}"));
+  Ok(())
+}
+
+#[test]
+fn test_diff() {
+  let mut old = OperationIds::default();
+  old
+    .insert_opid_with_path_method("list_users", "/users", "get")
+    .unwrap();
+  old
+    .insert_opid_with_path_method("get_user", "/users/{id}", "get")
+    .unwrap();
+  old
+    .insert_synthetic_opid_for_path_method("/legacy", "get")
+    .unwrap();
+
+  let mut new = OperationIds::default();
+  new
+    .insert_opid_with_path_method("list_users", "/users", "get")
+    .unwrap();
+  new
+    .insert_opid_with_path_method("get_user", "/v2/users/{id}", "get")
+    .unwrap();
+  new
+    .insert_synthetic_opid_for_path_method("/users", "post")
+    .unwrap();
+
+  let changes = diff(&old, &new);
+  assert_eq!(
+    changes.iter().map(ToString::to_string).collect::<Vec<_>>(),
+    [
+      "moved   get_user: GET /users/{id} -> GET /v2/users/{id}",
+      "removed legacy_get: GET /legacy",
+      "added   users_post: POST /users",
+    ]
+  );
+}
@@ -0,0 +1,72 @@
+use anyhow::{anyhow, Result};
+
+use crate::cli::ExplainArgs;
+use crate::generate::opids_for_file;
+use crate::opid::OperationIds;
+use crate::settings::Settings;
+
+/// Describe where an operation ID leads: the endpoint, its path
+/// parameters, and whether the spec spelled the operation ID out or it
+/// was generated. Returns [`None`] if there is no such operation ID.
+pub fn explain(opids: &OperationIds, operation_id: &str) -> Option<String> {
+  let (_, method) = opids.path_method_for_opid(operation_id)?;
+  let key = opids.path_method(operation_id)?;
+  let params = key.params();
+  let origin = opids.origin_of(operation_id)?;
+  Some(format!(
+    "operation id: {operation_id}\n\
+     path: {}\n\
+     method: {method}\n\
+     params: {}\n\
+     origin: {origin}\n",
+    key.path_with_params(),
+    if params.is_empty() {
+      "none".to_string()
+    } else {
+      params.join(", ")
+    }
+  ))
+}
+
+/// Run the `explain` command with `settings`
+pub fn run(args: &ExplainArgs, settings: &Settings) -> Result<()> {
+  let opids = opids_for_file(&args.spec, args.json5, settings)?;
+  let explanation = explain(&opids, &args.operation_id).ok_or_else(|| {
+    anyhow!(
+      "operation id {:?} not found in {}",
+      args.operation_id,
+      args.spec.display()
+    )
+  })?;
+  print!("{explanation}");
+  Ok(())
+}
+
+#[test]
+fn test_explain() {
+  let mut opids = OperationIds::default();
+  opids
+    .insert_opid_with_path_method("list_users", "/users", "get")
+    .unwrap();
+  opids
+    .insert_synthetic_opid_for_path_method("/users/{id}/posts/{post}", "delete")
+    .unwrap();
+
+  assert_eq!(
+    explain(&opids, "list_users").unwrap(),
+    "operation id: list_users\n\
+     path: /users\n\
+     method: get\n\
+     params: none\n\
+     origin: explicit\n"
+  );
+  assert_eq!(
+    explain(&opids, "users_posts_delete_by_id_by_post").unwrap(),
+    "operation id: users_posts_delete_by_id_by_post\n\
+     path: /users/{id}/posts/{post}\n\
+     method: delete\n\
+     params: id, post\n\
+     origin: synthetic\n"
+  );
+  assert_eq!(explain(&opids, "users_get"), None);
+}
@@ -0,0 +1,174 @@
+//! Exporting the operation IDs as source code, so code outside of the
+//! generated client can refer to operations by name.
+
+use anyhow::Result;
+
+use std::collections::BTreeSet;
+use std::fmt::Write;
+use std::path::Path;
+
+use crate::opid::OperationIds;
+use crate::output;
+
+/// Whether `name` can be a TypeScript enum member as it is. Other names are
+/// quoted.
+fn is_ts_identifier(name: &str) -> bool {
+  let mut chars = name.chars();
+  chars
+    .next()
+    .is_some_and(|c| c.is_ascii_alphabetic() || c == '_' || c == '$')
+    && chars.all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '$')
+}
+
+/// A TypeScript module exporting `enum OperationId` with a member for every
+/// operation ID, sorted by operation ID, whose value is the operation ID
+pub fn typescript_enum(opids: &OperationIds) -> String {
+  let mut code = "export enum OperationId {\n".to_string();
+  for (opid, _) in opids.iter() {
+    let value = serde_json::Value::from(opid).to_string();
+    let member = if is_ts_identifier(opid) {
+      opid.to_string()
+    } else {
+      value.clone()
+    };
+    writeln!(code, "  {member} = {value},").unwrap();
+  }
+  code.push_str("}\n");
+  code
+}
+
+/// Write [`typescript_enum`] to `path`, replacing the file
+pub fn write_typescript_enum(path: &Path, opids: &OperationIds) -> Result<()> {
+  let code = typescript_enum(opids);
+  output::write_output(path, true, |writer| Ok(writer.write_all(code.as_bytes())?))
+}
+
+/// `opid` as a PascalCase Rust identifier, like `UsersGet` for `users_get`:
+/// everything but ASCII letters and digits separates words, which start
+/// with a capital then. An `N` is prepended if it would start with a digit.
+fn rust_variant(opid: &str) -> String {
+  let mut variant = String::with_capacity(opid.len());
+  for word in opid
+    .split(|c: char| !c.is_ascii_alphanumeric())
+    .filter(|word| !word.is_empty())
+  {
+    let mut chars = word.chars();
+    variant.extend(chars.next().map(|c| c.to_ascii_uppercase()));
+    variant.extend(chars);
+  }
+  if variant.is_empty() {
+    variant.push_str("Unnamed");
+  }
+  if variant.starts_with(|c: char| c.is_ascii_digit()) {
+    variant.insert(0, 'N');
+  }
+  variant
+}
+
+/// Rust source of `enum OperationId` with a variant for every operation ID,
+/// sorted by operation ID, and an `as_str` method returning the operation ID
+/// of a variant. Operation IDs that only differ in what [`rust_variant`]
+/// drops get a numeric suffix, like `UsersGet1`, and so does `Self`, which
+/// cannot be a variant.
+pub fn rust_enum(opids: &OperationIds) -> String {
+  let mut taken = BTreeSet::from(["Self".to_string()]);
+  let variants = opids
+    .iter()
+    .map(|(opid, _)| {
+      let base = rust_variant(opid);
+      let variant = std::iter::once(base.clone())
+        .chain((1..).map(|attempt| format!("{base}{attempt}")))
+        .find(|variant| !taken.contains(variant))
+        .unwrap();
+      taken.insert(variant.clone());
+      (variant, opid)
+    })
+    .collect::<Vec<_>>();
+
+  let mut code = "/// The operations of the API\n\
+    #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]\n\
+    pub enum OperationId {\n"
+    .to_string();
+  for (variant, _) in &variants {
+    writeln!(code, "    {variant},").unwrap();
+  }
+  code.push_str(
+    "}\n\
+     \n\
+     impl OperationId {\n    \
+       /// The operation ID of the operation\n    \
+       pub fn as_str(self) -> &'static str {\n        \
+         match self {\n",
+  );
+  for (variant, opid) in &variants {
+    writeln!(code, "            OperationId::{variant} => {opid:?},").unwrap();
+  }
+  code.push_str("        }\n    }\n}\n");
+  code
+}
+
+/// Write [`rust_enum`] to `path`, replacing the file
+pub fn write_rust_enum(path: &Path, opids: &OperationIds) -> Result<()> {
+  let code = rust_enum(opids);
+  output::write_output(path, true, |writer| Ok(writer.write_all(code.as_bytes())?))
+}
+
+#[test]
+fn test_typescript_enum() {
+  let mut opids = OperationIds::default();
+  opids
+    .insert_synthetic_opid_for_path_method("/users", "get")
+    .unwrap();
+  opids
+    .insert_opid_with_path_method("billing.invoices-list", "/invoices", "get")
+    .unwrap();
+
+  assert_eq!(
+    typescript_enum(&opids),
+    "export enum OperationId {\n  \
+       \"billing.invoices-list\" = \"billing.invoices-list\",\n  \
+       users_get = \"users_get\",\n\
+     }\n"
+  );
+  assert!(!is_ts_identifier("2fa_get"));
+  assert!(is_ts_identifier("$users_get"));
+}
+
+#[test]
+fn test_rust_enum() {
+  let mut opids = OperationIds::default();
+  opids
+    .insert_synthetic_opid_for_path_method("/users", "get")
+    .unwrap();
+  opids
+    .insert_opid_with_path_method("usersGet", "/users", "post")
+    .unwrap();
+  opids
+    .insert_opid_with_path_method("2fa-check", "/2fa", "get")
+    .unwrap();
+  opids
+    .insert_opid_with_path_method("self", "/self", "get")
+    .unwrap();
+
+  let code = rust_enum(&opids);
+  assert!(code.contains("pub enum OperationId {\n"), "{code}");
+  assert!(code.contains("    UsersGet,\n"), "{code}");
+  assert!(
+    code.contains("OperationId::UsersGet => \"usersGet\",\n"),
+    "{code}"
+  );
+  // colliding after the conversion
+  assert!(
+    code.contains("OperationId::UsersGet1 => \"users_get\",\n"),
+    "{code}"
+  );
+  assert!(
+    code.contains("OperationId::N2faCheck => \"2fa-check\",\n"),
+    "{code}"
+  );
+  assert!(code.contains("OperationId::Self1 => \"self\",\n"), "{code}");
+  assert!(
+    code.contains("pub fn as_str(self) -> &'static str {\n"),
+    "{code}"
+  );
+}
@@ -0,0 +1,1062 @@
+use anyhow::{anyhow, Result};
+use openapiv3::{OpenAPI, Operation, PathItem, ReferenceOr};
+
+#[cfg(test)]
+use std::collections::BTreeMap;
+use std::collections::BTreeSet;
+use std::path::Path;
+
+use crate::input::read_spec;
+use crate::opid::{OperationIds, OperationInfo};
+use crate::settings::Settings;
+use crate::spec::{operation_names, operation_slot};
+use crate::strategy::{ByRequestContentType, DefaultStrategy, IdStrategy, NameMap};
+use crate::validate::validate_spec;
+use crate::warning::Warning;
+
+/// Extension telling whether the operation ID of an operation is
+/// `explicit` or `synthetic`, see [`Settings::annotate_origin`]
+pub const ORIGIN_EXTENSION: &str = "x-operation-origin";
+
+/// An operation of the spec, as far as assigning operation IDs is concerned
+struct Endpoint<'a> {
+  path: String,
+  method: String,
+  operation_id: Option<String>,
+  info: OperationInfo,
+  op: &'a Operation,
+}
+
+impl Endpoint<'_> {
+  /// `METHOD /path`, for error messages
+  fn name(&self) -> String {
+    format!("{} {}", self.method.to_uppercase(), self.path)
+  }
+}
+
+/// All operations of the spec, sorted canonically by path and method.
+/// Operations with one of the [`Settings::skip_methods`] are left out, as if
+/// the spec did not have them.
+fn endpoints<'a>(spec: &'a OpenAPI, settings: &Settings) -> Vec<Endpoint<'a>> {
+  let skip_methods = &settings.skip_methods;
+  let mut endpoints: Vec<Endpoint> = spec
+    .paths
+    .paths
+    .iter()
+    .filter_map(|(path, item)| item.as_item().map(|item| (path, item)))
+    .flat_map(|(path, item)| {
+      let operations = move || {
+        item
+          .iter()
+          .filter(move |(method, _)| !skip_methods.contains(*method))
+      };
+      let shares_path = operations().count() > 1;
+      operations().map(move |(method, op)| Endpoint {
+        path: path.clone(),
+        method: method.to_string(),
+        operation_id: op.operation_id.clone(),
+        info: OperationInfo {
+          shares_path,
+          ..OperationInfo::from(op)
+        },
+        op,
+      })
+    })
+    .collect();
+  // shorter paths come first, so they keep the plain name on a collision
+  endpoints.sort_by(|a, b| {
+    (a.path.len(), &a.path, &a.method).cmp(&(b.path.len(), &b.path, &b.method))
+  });
+  endpoints
+}
+
+/// Replace path items that are only a `$ref` into the spec itself, like
+/// `#/x-path-items/users`, by the path item it points to, so their
+/// operations get operation IDs too. References to other files are left to
+/// [`crate::bundle::bundle`].
+fn resolve_path_item_refs(spec: &mut OpenAPI) -> Result<()> {
+  let document = serde_json::to_value(&*spec)?;
+  for (path, item) in spec.paths.paths.iter_mut() {
+    let ReferenceOr::Reference { reference } = item else {
+      continue;
+    };
+    let pointer = reference.strip_prefix('#').ok_or_else(|| {
+      anyhow!(
+        "cannot resolve {reference:?} of path {path}, only references into the spec are"
+      )
+    })?;
+    let resolved = document
+      .pointer(pointer)
+      .ok_or_else(|| anyhow!("cannot resolve {reference:?} of path {path}"))?;
+    let resolved: PathItem = serde_json::from_value(resolved.clone())
+      .map_err(|err| anyhow!("{reference:?} of path {path} is no path item: {err}"))?;
+    *item = ReferenceOr::Item(resolved);
+  }
+  Ok(())
+}
+
+/// Paths whose path item is only a `$ref`, so they have no operations to
+/// assign operation IDs to
+fn ref_only_paths(spec: &OpenAPI) -> Vec<&str> {
+  spec
+    .paths
+    .paths
+    .iter()
+    .filter(|(_, item)| item.as_item().is_none())
+    .map(|(path, _)| path.as_str())
+    .collect()
+}
+
+/// Assign operation IDs to every operation in the spec that lacks one.
+///
+/// The result must not depend on the order in which paths and methods appear
+/// in the input document, since that order decides which endpoint keeps the
+/// plain name and which one receives a collision suffix. All path/method pairs
+/// are therefore collected and sorted canonically first: shorter paths before
+/// longer ones, then by path, then by method. So of two colliding paths, the
+/// shorter one keeps the plain name, or the lexicographically smaller one if
+/// they are equally long.
+/// Explicit operation IDs are registered before any synthetic ID is generated,
+/// so generated names can never claim an ID that the spec spells out later.
+/// Unless [`Settings::write_back`] is disabled, the operation IDs are then
+/// written into the operations lacking one. Returns the assigned operation
+/// IDs. With [`Settings::case_insensitive_paths`], only the first of the
+/// endpoints sharing an operation ID keeps it, the operations of the others
+/// are removed from the spec with a warning, and so are the path items they
+/// leave empty.
+///
+/// Path items that are only a `$ref` are skipped with a warning, unless
+/// [`Settings::resolve_refs`] inlines them first.
+///
+/// With [`Settings::request_content_type`], operations are told apart by
+/// the content type of their request body, see [`ByRequestContentType`].
+/// With [`Settings::info_operation_names`], operations lacking an operation
+/// ID are named by the [`crate::spec::OPERATION_NAMES_EXTENSION`] of the
+/// spec where it has an entry for them, like by a mapping file.
+pub fn gen_operation_ids(
+  spec: &mut OpenAPI,
+  settings: &Settings,
+) -> Result<OperationIds> {
+  let strategy = default_strategy(spec, settings)?;
+  gen_operation_ids_with_strategy(spec, settings, &*strategy)
+}
+
+/// The strategy [`gen_operation_ids`] uses for `spec` with `settings`
+pub fn default_strategy(
+  spec: &OpenAPI,
+  settings: &Settings,
+) -> Result<Box<dyn IdStrategy>> {
+  let strategy: Box<dyn IdStrategy> = if settings.request_content_type {
+    Box::new(ByRequestContentType)
+  } else {
+    Box::new(DefaultStrategy)
+  };
+  if !settings.info_operation_names {
+    return Ok(strategy);
+  }
+  Ok(Box::new(NameMap {
+    names: operation_names(spec)?,
+    fallback: strategy,
+  }))
+}
+
+/// The first `count` operation IDs [`gen_operation_ids`] would generate for
+/// `spec`, in the order they are generated. Assigning stops once there are
+/// `count`, and the operation IDs are assigned to a copy of `spec`, so this
+/// is quick to check on huge specs.
+pub fn preview(spec: &OpenAPI, settings: &Settings, count: usize) -> Result<Vec<String>> {
+  let strategy = default_strategy(spec, settings)?;
+  let mut spec = spec.clone();
+  let (_, generated) =
+    assign_operation_ids(&mut spec, settings, &*strategy, Some(count))?;
+  Ok(generated)
+}
+
+/// Like [`gen_operation_ids`], but `strategy` gets to pick the operation ID
+/// of every operation lacking one before the default synthetic ID is used.
+pub fn gen_operation_ids_with_strategy(
+  spec: &mut OpenAPI,
+  settings: &Settings,
+  strategy: &dyn IdStrategy,
+) -> Result<OperationIds> {
+  let (mut opids, _) = assign_operation_ids(spec, settings, strategy, None)?;
+
+  if !settings.write_back {
+    return Ok(opids);
+  }
+  // Endpoints merged by `case_insensitive_paths` share their operation ID,
+  // which a spec may only carry once, whether it was explicit or not. The
+  // first of them in canonical order keeps it, the operations of the
+  // others are removed, along with path items left without operations.
+  let mut owned = BTreeSet::new();
+  let merged = endpoints(spec, settings)
+    .into_iter()
+    .filter_map(|endpoint| {
+      let opid = opids.opid_for_path_method(&endpoint.path, &endpoint.method)?;
+      (!owned.insert(opid.to_string())).then(|| {
+        (
+          endpoint.name(),
+          endpoint.path,
+          endpoint.method,
+          opid.to_string(),
+        )
+      })
+    })
+    .collect::<Vec<_>>();
+  let mut emptied = BTreeSet::new();
+  for (name, path, method, opid) in merged {
+    if let Some(item) = spec
+      .paths
+      .paths
+      .get_mut(&path)
+      .and_then(|item| item.as_item_mut())
+      && let Some(slot) = operation_slot(item, &method)
+    {
+      *slot = None;
+      if item.iter().next().is_none() {
+        emptied.insert(path);
+      }
+    }
+    opids.add_warning(Warning::new(format!(
+      "removed {name} from the output, it only differs in case from the endpoint \
+       with operation id {opid:?}"
+    )));
+  }
+  spec.paths.paths.retain(|path, _| !emptied.contains(path));
+  spec.paths.paths.iter_mut().for_each(|(path, item)| {
+    if let Some(item) = item.as_item_mut() {
+      item.iter_mut().for_each(|(method, op)| {
+        if settings.skip_methods.contains(method) {
+          return;
+        }
+        if op.operation_id.is_none() || settings.sanitize_explicit {
+          op.operation_id = opids.opid_for_path_method(path, method).map(String::from);
+        }
+        if settings.annotate_origin
+          && let Some(origin) = op
+            .operation_id
+            .as_deref()
+            .and_then(|opid| opids.origin_of(opid))
+        {
+          op.extensions
+            .insert(ORIGIN_EXTENSION.to_string(), origin.to_string().into());
+        }
+      });
+    }
+  });
+  Ok(opids)
+}
+
+/// Assign operation IDs to the operations of `spec` lacking one, without
+/// writing them into `spec`. Stops once `limit` operation IDs were
+/// generated. Returns the operation IDs, along with the generated ones in
+/// the order they were generated.
+fn assign_operation_ids(
+  spec: &mut OpenAPI,
+  settings: &Settings,
+  strategy: &dyn IdStrategy,
+  limit: Option<usize>,
+) -> Result<(OperationIds, Vec<String>)> {
+  if settings.resolve_refs {
+    resolve_path_item_refs(spec)?;
+  }
+  let mut opids = OperationIds::with_settings(settings.clone());
+  let skipped = ref_only_paths(spec);
+  if !skipped.is_empty() {
+    opids.add_warning(Warning::new(format!(
+      "skipped {} path items that are only a $ref: {}",
+      skipped.len(),
+      skipped.join(", ")
+    )));
+  }
+  if settings.base_path_in_id
+    && let Some(server) = spec.servers.first()
+  {
+    opids.set_base_path(&server.url)?;
+  }
+  // With `collect_errors`, failures are recorded and reported together
+  // once every operation had its turn, otherwise the first one is returned
+  let mut errors = Vec::new();
+  let mut record = |result: Result<()>, context: &str| match result {
+    Err(err) if settings.collect_errors => {
+      errors.push(format!("{context}: {err}"));
+      Ok(())
+    }
+    result => result,
+  };
+
+  for opid in &settings.reserved_operation_ids {
+    record(opids.reserve(opid), "reserved operation ids")?;
+  }
+  let endpoints = endpoints(spec, settings);
+  for endpoint in &endpoints {
+    if let Some(opid) = &endpoint.operation_id {
+      let result = if settings.sanitize_explicit {
+        opids
+          .insert_sanitized_opid_with_path_method(opid, &endpoint.path, &endpoint.method)
+          .map(|_| ())
+      } else {
+        opids.insert_opid_with_path_method(opid, &endpoint.path, &endpoint.method)
+      };
+      record(result, &endpoint.name())?;
+    }
+  }
+  let mut generated = Vec::new();
+  for endpoint in endpoints
+    .iter()
+    .filter(|endpoint| endpoint.operation_id.is_none())
+  {
+    if limit.is_some_and(|limit| generated.len() >= limit) {
+      break;
+    }
+    let result = match strategy.id(&endpoint.path, &endpoint.method, endpoint.op, &opids)
+    {
+      Some(opid) => opids
+        .insert_chosen_opid_for_path_method(&opid, &endpoint.path, &endpoint.method)
+        .map(|()| generated.push(opid)),
+      None => match endpoint
+        .op
+        .tags
+        .iter()
+        .find(|tag| settings.deny_synthetic_tags.contains(*tag))
+      {
+        Some(tag) => Err(anyhow!(
+          "{} is tagged {tag:?} and needs an explicit operation id",
+          endpoint.name()
+        )),
+        None => {
+          let info = OperationInfo {
+            discriminator: strategy.discriminator(
+              &endpoint.path,
+              &endpoint.method,
+              endpoint.op,
+            ),
+            ..endpoint.info.clone()
+          };
+          opids
+            .insert_synthetic_opid_for_operation(&endpoint.path, &endpoint.method, &info)
+            .map(|opid| generated.push(opid))
+        }
+      },
+    };
+    record(result, &endpoint.name())?;
+  }
+  if !errors.is_empty() {
+    return Err(anyhow!(
+      "{} errors while assigning operation ids:\n  {}",
+      errors.len(),
+      errors.join("\n  ")
+    ));
+  }
+  Ok((opids, generated))
+}
+
+/// Read the spec at `path` and assign operation IDs, returning only the
+/// operation IDs
+pub fn opids_for_file(
+  path: &Path,
+  json5: bool,
+  settings: &Settings,
+) -> Result<OperationIds> {
+  let mut spec = read_spec(path, json5)?;
+  validate_spec(&spec)?;
+  gen_operation_ids(&mut spec, settings)
+}
+
+#[cfg(test)]
+pub fn collect_opids(spec: &OpenAPI) -> BTreeMap<(String, String), Option<String>> {
+  spec
+    .paths
+    .paths
+    .iter()
+    .filter_map(|(path, item)| item.as_item().map(|item| (path, item)))
+    .flat_map(|(path, item)| {
+      item.iter().map(move |(method, op)| {
+        ((path.clone(), method.to_string()), op.operation_id.clone())
+      })
+    })
+    .collect()
+}
+
+#[test]
+fn test_gen_operation_ids_order_independent() {
+  // "/foo/bar" and "/foo_bar" collide on `foo_bar_get`, and the explicit
+  // `b_get` on "/a" would collide with the synthetic ID for "/b" if that
+  // one was generated first.
+  let forward = r#"{
+    "openapi": "3.0.1",
+    "info": { "title": "test", "version": "1" },
+    "paths": {
+      "/b": { "get": { "responses": {} } },
+      "/foo_bar": { "get": { "responses": {} } },
+      "/foo/bar": { "get": { "responses": {} }, "post": { "responses": {} } },
+      "/a": { "get": { "operationId": "b_get", "responses": {} } }
+    }
+  }"#;
+  let backward = r#"{
+    "openapi": "3.0.1",
+    "info": { "title": "test", "version": "1" },
+    "paths": {
+      "/a": { "get": { "operationId": "b_get", "responses": {} } },
+      "/foo/bar": { "post": { "responses": {} }, "get": { "responses": {} } },
+      "/foo_bar": { "get": { "responses": {} } },
+      "/b": { "get": { "responses": {} } }
+    }
+  }"#;
+
+  let mut forward: OpenAPI = serde_json::from_str(forward).unwrap();
+  let mut backward: OpenAPI = serde_json::from_str(backward).unwrap();
+  gen_operation_ids(&mut forward, &Settings::default()).unwrap();
+  gen_operation_ids(&mut backward, &Settings::default()).unwrap();
+
+  let opids = collect_opids(&forward);
+  assert_eq!(opids, collect_opids(&backward));
+  let opid = |path: &str, method: &str| {
+    opids[&(path.to_string(), method.to_string())]
+      .clone()
+      .unwrap()
+  };
+  assert_eq!(opid("/a", "get"), "b_get");
+  assert_eq!(opid("/b", "get"), "b1_get");
+  assert_eq!(opid("/foo/bar", "get"), "foo_bar_get");
+  assert_eq!(opid("/foo/bar", "post"), "foo_bar_post");
+  assert_eq!(opid("/foo_bar", "get"), "foo_bar1_get");
+}
+
+#[test]
+fn test_gen_operation_ids_every_method() {
+  let ops = crate::spec::METHODS
+    .iter()
+    .map(|method| format!(r#""{method}": {{ "responses": {{}} }}"#))
+    .collect::<Vec<_>>()
+    .join(", ");
+  let mut spec = crate::spec::mk_spec(&format!(r#"{{ "/pets/{{id}}": {{ {ops} }} }}"#));
+  let opids = gen_operation_ids(&mut spec, &Settings::default()).unwrap();
+
+  let assigned = collect_opids(&spec);
+  assert_eq!(assigned.len(), crate::spec::METHODS.len());
+  crate::spec::METHODS.iter().for_each(|method| {
+    let opid = assigned[&("/pets/{id}".to_string(), method.to_string())]
+      .clone()
+      .unwrap();
+    assert_eq!(opid, format!("pets_{method}_by_id"));
+    assert_eq!(
+      opids.opid_for_path_method("/pets/{id}", method),
+      Some(opid.as_str())
+    );
+  });
+  let unique = assigned.values().collect::<std::collections::BTreeSet<_>>();
+  assert_eq!(unique.len(), assigned.len());
+  assert_eq!(opids.stats().synthetic, crate::spec::METHODS.len());
+}
+
+#[test]
+fn test_gen_operation_ids_base_path_in_id() {
+  let mut spec: OpenAPI = serde_json::from_str(
+    r#"{
+      "openapi": "3.0.1",
+      "info": { "title": "test", "version": "1" },
+      "servers": [
+        { "url": "https://api.example.com/v2" },
+        { "url": "https://staging.example.com/beta" }
+      ],
+      "paths": {
+        "/users": {
+          "get": { "responses": {} },
+          "post": { "operationId": "create_user", "responses": {} }
+        }
+      }
+    }"#,
+  )
+  .unwrap();
+  let settings = Settings {
+    base_path_in_id: true,
+    ..Settings::default()
+  };
+  let opids = gen_operation_ids(&mut spec, &settings).unwrap();
+  assert_eq!(
+    opids.opid_for_path_method("/users", "get"),
+    Some("v2_users_get")
+  );
+  // explicit operation IDs are left alone
+  assert_eq!(
+    opids.opid_for_path_method("/users", "post"),
+    Some("create_user")
+  );
+}
+
+#[test]
+fn test_gen_operation_ids_without_write_back() {
+  let paths = r#"{
+    "/users": {
+      "get": { "operationId": "list_users", "responses": {} },
+      "post": { "responses": {} }
+    }
+  }"#;
+  let mut spec = crate::spec::mk_spec(paths);
+  let settings = Settings {
+    write_back: false,
+    ..Settings::default()
+  };
+  let opids = gen_operation_ids(&mut spec, &settings).unwrap();
+
+  assert_eq!(
+    serde_json::to_value(&spec).unwrap(),
+    serde_json::to_value(crate::spec::mk_spec(paths)).unwrap()
+  );
+  assert_eq!(
+    opids.opid_for_path_method("/users", "get"),
+    Some("list_users")
+  );
+  assert_eq!(
+    opids.opid_for_path_method("/users", "post"),
+    Some("users_post")
+  );
+}
+
+#[test]
+fn test_gen_operation_ids_annotate_origin() {
+  let paths = r#"{
+    "/users": {
+      "get": { "operationId": "list_users", "responses": {} },
+      "post": { "responses": {} }
+    }
+  }"#;
+  let origin = |spec: &OpenAPI, method: &str| {
+    let item = spec.paths.paths["/users"].as_item().unwrap();
+    let (_, op) = item.iter().find(|(other, _)| *other == method).unwrap();
+    op.extensions.get(ORIGIN_EXTENSION).cloned()
+  };
+
+  let mut spec = crate::spec::mk_spec(paths);
+  gen_operation_ids(&mut spec, &Settings::default()).unwrap();
+  assert_eq!(origin(&spec, "get"), None);
+
+  let settings = Settings {
+    annotate_origin: true,
+    ..Settings::default()
+  };
+  let mut spec = crate::spec::mk_spec(paths);
+  gen_operation_ids(&mut spec, &settings).unwrap();
+  assert_eq!(origin(&spec, "get"), Some("explicit".into()));
+  assert_eq!(origin(&spec, "post"), Some("synthetic".into()));
+}
+
+#[test]
+fn test_gen_operation_ids_ref_only_path_items() {
+  let spec = r##"{
+    "openapi": "3.0.1",
+    "info": { "title": "test", "version": "1" },
+    "paths": {
+      "/users": { "$ref": "#/x-path-items/users" },
+      "/people": { "$ref": "#/x-path-items/users" },
+      "/groups": { "get": { "responses": {} } }
+    },
+    "x-path-items": { "users": { "get": { "responses": {} } } }
+  }"##;
+
+  let mut skipping: OpenAPI = serde_json::from_str(spec).unwrap();
+  let opids = gen_operation_ids(&mut skipping, &Settings::default()).unwrap();
+  assert_eq!(opids.stats().synthetic, 1);
+  assert_eq!(
+    opids.warnings()[0].message,
+    "skipped 2 path items that are only a $ref: /users, /people"
+  );
+
+  let settings = Settings {
+    resolve_refs: true,
+    ..Settings::default()
+  };
+  let mut resolving: OpenAPI = serde_json::from_str(spec).unwrap();
+  let opids = gen_operation_ids(&mut resolving, &settings).unwrap();
+  assert!(opids.warnings().is_empty());
+  let assigned = collect_opids(&resolving);
+  assert_eq!(
+    assigned[&("/users".to_string(), "get".to_string())].as_deref(),
+    Some("users_get")
+  );
+  assert_eq!(
+    assigned[&("/people".to_string(), "get".to_string())].as_deref(),
+    Some("people_get")
+  );
+
+  let mut external = crate::spec::mk_spec(r#"{ "/users": { "$ref": "./users.json" } }"#);
+  assert!(gen_operation_ids(&mut external, &settings).is_err());
+}
+
+#[test]
+fn test_gen_operation_ids_skip_methods() {
+  let paths = r#"{
+    "/users": {
+      "get": { "responses": {} },
+      "options": { "operationId": "users_get", "responses": {} }
+    },
+    "/groups": {
+      "options": { "responses": {} }
+    }
+  }"#;
+  let settings = Settings {
+    skip_methods: ["options".to_string()].into(),
+    method_suffix: crate::settings::MethodSuffix::Auto,
+    ..Settings::default()
+  };
+  let mut spec = crate::spec::mk_spec(paths);
+  let opids = gen_operation_ids(&mut spec, &settings).unwrap();
+
+  let assigned = collect_opids(&spec);
+  // the skipped explicit `users_get` neither collides nor makes the path
+  // shared
+  assert_eq!(
+    assigned[&("/users".to_string(), "get".to_string())].as_deref(),
+    Some("users")
+  );
+  assert_eq!(
+    assigned[&("/groups".to_string(), "options".to_string())],
+    None
+  );
+  assert_eq!(opids.stats().explicit, 0);
+  assert_eq!(opids.stats().synthetic, 1);
+
+  assert_eq!(
+    crate::spec::remove_methods(&mut spec, &settings.skip_methods),
+    2
+  );
+  assert_eq!(collect_opids(&spec).len(), 1);
+}
+
+#[test]
+fn test_gen_operation_ids_sanitize_explicit() {
+  let paths = r#"{
+    "/users": {
+      "get": { "operationId": "listUsers", "responses": {} },
+      "post": { "operationId": "Create-User", "responses": {} }
+    },
+    "/users/{id}": {
+      "get": { "responses": {} },
+      "delete": { "operationId": "delete_user", "responses": {} }
+    },
+    "/v1/users": { "get": { "operationId": "list_users", "responses": {} } }
+  }"#;
+  let settings = Settings {
+    sanitize_explicit: true,
+    ..Settings::default()
+  };
+  let mut spec = crate::spec::mk_spec(paths);
+  gen_operation_ids(&mut spec, &settings).unwrap();
+
+  let opid = |path: &str, method: &str| {
+    collect_opids(&spec)[&(path.to_string(), method.to_string())]
+      .clone()
+      .unwrap()
+  };
+  assert_eq!(opid("/users", "get"), "list_users");
+  assert_eq!(opid("/users", "post"), "create_user");
+  assert_eq!(opid("/users/{id}", "get"), "users_get_by_id");
+  assert_eq!(opid("/users/{id}", "delete"), "delete_user");
+  // sanitizing made it collide with the first one
+  assert_eq!(opid("/v1/users", "get"), "list_users1");
+
+  // without the setting, the explicit operation IDs stay as they are
+  let mut spec = crate::spec::mk_spec(paths);
+  gen_operation_ids(&mut spec, &Settings::default()).unwrap();
+  assert_eq!(
+    collect_opids(&spec)[&("/users".to_string(), "get".to_string())].as_deref(),
+    Some("listUsers")
+  );
+}
+
+#[test]
+fn test_gen_operation_ids_collect_errors() {
+  let paths = r#"{
+    "/a": { "get": { "operationId": "same", "responses": {} } },
+    "/b": { "get": { "operationId": "same", "responses": {} } },
+    "/c": { "get": { "operationId": "special", "responses": {} } },
+    "/d": { "get": { "responses": {} } }
+  }"#;
+  let settings = Settings {
+    reserved_operation_ids: vec!["special".to_string()],
+    ..Settings::default()
+  };
+
+  // fail fast by default
+  let err = gen_operation_ids(&mut crate::spec::mk_spec(paths), &settings).unwrap_err();
+  assert_eq!(err.to_string(), "operation id is already present: \"same\"");
+
+  let settings = Settings {
+    collect_errors: true,
+    ..settings
+  };
+  let err = gen_operation_ids(&mut crate::spec::mk_spec(paths), &settings).unwrap_err();
+  assert_eq!(
+    err.to_string(),
+    "2 errors while assigning operation ids:\n  \
+     GET /b: operation id is already present: \"same\"\n  \
+     GET /c: operation id is reserved: \"special\""
+  );
+}
+
+#[test]
+fn test_gen_operation_ids_with_strategy() {
+  /// Names operations after their summary, unless that is taken
+  struct BySummary;
+
+  impl IdStrategy for BySummary {
+    fn id(
+      &self,
+      _path: &str,
+      _method: &str,
+      op: &Operation,
+      ids: &OperationIds,
+    ) -> Option<String> {
+      op.summary
+        .clone()
+        .filter(|summary| ids.path_method_for_opid(summary).is_none())
+    }
+  }
+
+  let mut spec = crate::spec::mk_spec(
+    r#"{
+      "/users": {
+        "get": { "summary": "list_users", "responses": {} },
+        "post": { "responses": {} }
+      },
+      "/users/{id}": {
+        "get": { "summary": "list_users", "responses": {} },
+        "delete": { "operationId": "remove_user", "responses": {} }
+      }
+    }"#,
+  );
+  let opids =
+    gen_operation_ids_with_strategy(&mut spec, &Settings::default(), &BySummary).unwrap();
+
+  let opid = |path: &str, method: &str| opids.opid_for_path_method(path, method);
+  assert_eq!(opid("/users", "get"), Some("list_users"));
+  assert_eq!(opid("/users", "post"), Some("users_post"));
+  // the summary is taken, so the default applies
+  assert_eq!(opid("/users/{id}", "get"), Some("users_get_by_id"));
+  assert_eq!(opid("/users/{id}", "delete"), Some("remove_user"));
+  assert_eq!(opids.stats().synthetic, 3);
+}
+
+#[test]
+fn test_gen_operation_ids_with_discriminator() {
+  /// Tells operations apart by the media type of their first response
+  struct ByMediaType;
+
+  impl IdStrategy for ByMediaType {
+    fn id(
+      &self,
+      _path: &str,
+      _method: &str,
+      _op: &Operation,
+      _ids: &OperationIds,
+    ) -> Option<String> {
+      None
+    }
+
+    fn discriminator(
+      &self,
+      _path: &str,
+      _method: &str,
+      op: &Operation,
+    ) -> Option<String> {
+      let (_, response) = op.responses.responses.first()?;
+      let media_type = response.as_item()?.content.keys().next()?;
+      Some(media_type.rsplit('/').next()?.to_string())
+    }
+  }
+
+  let paths = r#"{
+    "/report": { "get": { "responses": {
+      "200": { "description": "ok", "content": { "text/csv": {} } }
+    } } },
+    "/report/": { "get": { "responses": {
+      "200": { "description": "ok", "content": { "application/json": {} } }
+    } } }
+  }"#;
+  let mut spec = crate::spec::mk_spec(paths);
+  let opids =
+    gen_operation_ids_with_strategy(&mut spec, &Settings::default(), &ByMediaType)
+      .unwrap();
+  assert_eq!(
+    opids.opid_for_path_method("/report", "get"),
+    Some("report_get_csv")
+  );
+  assert_eq!(
+    opids.opid_for_path_method("/report/", "get"),
+    Some("report_get_json")
+  );
+
+  // the default strategy never appends anything
+  let mut spec = crate::spec::mk_spec(paths);
+  let opids = gen_operation_ids(&mut spec, &Settings::default()).unwrap();
+  assert_eq!(
+    opids.opid_for_path_method("/report", "get"),
+    Some("report_get")
+  );
+  assert_eq!(
+    opids.opid_for_path_method("/report/", "get"),
+    Some("report1_get")
+  );
+}
+
+#[test]
+fn test_gen_operation_ids_keeps_parameter_refs() {
+  let mut spec: OpenAPI = serde_json::from_str(
+    r##"{
+      "openapi": "3.0.1",
+      "info": { "title": "test", "version": "1" },
+      "paths": {
+        "/users": {
+          "get": {
+            "parameters": [ { "$ref": "#/components/parameters/PageParam" } ],
+            "responses": {}
+          }
+        }
+      },
+      "components": {
+        "parameters": {
+          "PageParam": {
+            "name": "page",
+            "in": "query",
+            "schema": { "type": "integer" }
+          }
+        }
+      }
+    }"##,
+  )
+  .unwrap();
+  gen_operation_ids(&mut spec, &Settings::default()).unwrap();
+
+  let output = serde_json::to_string_pretty(&spec).unwrap();
+  let output: serde_json::Value = serde_json::from_str(&output).unwrap();
+  let op = &output["paths"]["/users"]["get"];
+  assert_eq!(op["operationId"], "users_get");
+  assert_eq!(
+    op["parameters"],
+    serde_json::json!([{ "$ref": "#/components/parameters/PageParam" }])
+  );
+  assert_eq!(
+    output["components"]["parameters"]["PageParam"]["name"],
+    "page"
+  );
+
+  // and the output is still a spec
+  assert!(serde_json::from_value::<OpenAPI>(output).is_ok());
+}
+
+#[test]
+fn test_shorter_path_keeps_the_plain_name() {
+  // `/a-b` sorts before `/ab`, but `/ab` is shorter
+  for paths in [
+    r#"{ "/a-b": { "get": { "responses": {} } }, "/ab": { "get": { "responses": {} } } }"#,
+    r#"{ "/ab": { "get": { "responses": {} } }, "/a-b": { "get": { "responses": {} } } }"#,
+  ] {
+    let settings = Settings {
+      intra_segment: crate::settings::IntraSegment::Join,
+      ..Settings::default()
+    };
+    let opids = gen_operation_ids(&mut crate::spec::mk_spec(paths), &settings).unwrap();
+    assert_eq!(opids.opid_for_path_method("/ab", "get"), Some("ab_get"));
+    assert_eq!(opids.opid_for_path_method("/a-b", "get"), Some("ab1_get"));
+  }
+}
+
+#[test]
+fn test_request_content_type() {
+  let paths = r#"{
+    "/files": {
+      "post": { "requestBody": { "content": { "multipart/form-data": {} } }, "responses": {} },
+      "put": { "requestBody": { "content": {
+        "application/json": {}, "application/xml": {}
+      } }, "responses": {} },
+      "get": { "responses": {} }
+    },
+    "/files/": {
+      "post": { "requestBody": { "content": { "application/json": {} } }, "responses": {} }
+    }
+  }"#;
+  let settings = Settings {
+    request_content_type: true,
+    ..Settings::default()
+  };
+  let opids = gen_operation_ids(&mut crate::spec::mk_spec(paths), &settings).unwrap();
+  let opid = |path, method| opids.opid_for_path_method(path, method);
+  assert_eq!(opid("/files", "post"), Some("files_post_multipart"));
+  assert_eq!(opid("/files/", "post"), Some("files_post_json"));
+  // several content types are nothing to tell apart by, and neither is none
+  assert_eq!(opid("/files", "put"), Some("files_put"));
+  assert_eq!(opid("/files", "get"), Some("files_get"));
+
+  // off by default
+  let opids =
+    gen_operation_ids(&mut crate::spec::mk_spec(paths), &Settings::default()).unwrap();
+  assert_eq!(
+    opids.opid_for_path_method("/files", "post"),
+    Some("files_post")
+  );
+}
+
+#[test]
+fn test_preview() {
+  let spec = crate::spec::mk_spec(
+    r#"{
+      "/users": {
+        "get": { "responses": {} },
+        "post": { "operationId": "create_user", "responses": {} }
+      },
+      "/groups": { "get": { "responses": {} } },
+      "/users/{id}": { "get": { "responses": {} }, "delete": { "responses": {} } }
+    }"#,
+  );
+  let settings = Settings::default();
+  assert_eq!(
+    preview(&spec, &settings, 2).unwrap(),
+    ["users_get", "groups_get"]
+  );
+  assert_eq!(preview(&spec, &settings, 10).unwrap().len(), 4);
+  // the spec itself is left alone
+  assert_eq!(collect_opids(&spec)[&("/users".into(), "get".into())], None);
+}
+
+#[test]
+fn test_deny_synthetic_for_tag() {
+  let paths = r#"{
+    "/health": { "get": { "responses": {} } },
+    "/users": {
+      "get": { "tags": ["public"], "operationId": "list_users", "responses": {} },
+      "post": { "tags": ["admin", "public"], "responses": {} }
+    }
+  }"#;
+  let settings = Settings {
+    deny_synthetic_tags: ["public".to_string()].into(),
+    ..Settings::default()
+  };
+  let err = gen_operation_ids(&mut crate::spec::mk_spec(paths), &settings).unwrap_err();
+  assert_eq!(
+    err.to_string(),
+    "POST /users is tagged \"public\" and needs an explicit operation id"
+  );
+
+  let mut spec = crate::spec::mk_spec(&paths.replace(r#""public"]"#, r#""partner"]"#));
+  let opids = gen_operation_ids(&mut spec, &settings).unwrap();
+  assert_eq!(
+    opids.opid_for_path_method("/health", "get"),
+    Some("health_get")
+  );
+  assert_eq!(
+    opids.opid_for_path_method("/users", "post"),
+    Some("users_post")
+  );
+}
+
+#[test]
+fn test_info_operation_names() {
+  let paths = r#"{
+    "/users": {
+      "get": { "responses": {} },
+      "post": { "operationId": "create_user", "responses": {} }
+    },
+    "/groups": { "get": { "responses": {} } }
+  }"#;
+  let settings = Settings {
+    info_operation_names: true,
+    ..Settings::default()
+  };
+  let with_names = |names: serde_json::Value| {
+    let mut spec = crate::spec::mk_spec(paths);
+    spec
+      .info
+      .extensions
+      .insert(crate::spec::OPERATION_NAMES_EXTENSION.to_string(), names);
+    spec
+  };
+
+  let mut spec = with_names(serde_json::json!({
+    "GET /users": "list_users",
+    "post /users": "add_user"
+  }));
+  let opids = gen_operation_ids(&mut spec, &settings).unwrap();
+  assert_eq!(
+    opids.opid_for_path_method("/users", "get"),
+    Some("list_users")
+  );
+  // explicit operation IDs win, like over a mapping file
+  assert_eq!(
+    opids.opid_for_path_method("/users", "post"),
+    Some("create_user")
+  );
+  assert_eq!(
+    opids.opid_for_path_method("/groups", "get"),
+    Some("groups_get")
+  );
+
+  let err = gen_operation_ids(
+    &mut with_names(serde_json::json!({ "/users": "x" })),
+    &settings,
+  )
+  .unwrap_err();
+  assert_eq!(
+    err.to_string(),
+    "invalid x-operation-names entry \"/users\", expected \"METHOD /path\""
+  );
+  let err = gen_operation_ids(
+    &mut with_names(serde_json::json!({ "GET /users": 1 })),
+    &settings,
+  )
+  .unwrap_err();
+  assert_eq!(
+    err.to_string(),
+    "x-operation-names entry \"GET /users\" is no operation id: 1"
+  );
+  assert!(gen_operation_ids(&mut with_names(serde_json::json!([])), &settings).is_err());
+}
+
+#[test]
+fn test_case_insensitive_paths_write_back() {
+  let settings = Settings {
+    case_insensitive_paths: true,
+    ..Settings::default()
+  };
+  let mut spec = crate::spec::mk_spec(
+    r#"{
+      "/Users": { "get": { "responses": {} } },
+      "/users": {
+        "get": { "operationId": "list_users", "responses": {} },
+        "post": { "responses": {} }
+      }
+    }"#,
+  );
+  let opids = gen_operation_ids(&mut spec, &settings).unwrap();
+
+  // the output carries every operation ID once, on the first endpoint
+  assert_eq!(
+    collect_opids(&spec),
+    BTreeMap::from([
+      (("/Users".into(), "get".into()), Some("list_users".into())),
+      (("/users".into(), "post".into()), Some("users_post".into())),
+    ])
+  );
+  assert_eq!(
+    opids.warnings(),
+    [Warning::new(
+      "removed GET /users from the output, it only differs in case from the endpoint \
+       with operation id \"list_users\""
+    )]
+  );
+
+  // explicit duplicates are removed as well, along with the path item they
+  // leave empty
+  let mut spec = crate::spec::mk_spec(
+    r#"{
+      "/users": { "get": { "operationId": "list_users", "responses": {} } },
+      "/Users": { "get": { "operationId": "list_users", "responses": {} } }
+    }"#,
+  );
+  gen_operation_ids(&mut spec, &settings).unwrap();
+  assert_eq!(
+    collect_opids(&spec),
+    BTreeMap::from([(("/Users".into(), "get".into()), Some("list_users".into()))])
+  );
+  assert!(!spec.paths.paths.contains_key("/users"));
+}
@@ -0,0 +1,461 @@
+use anyhow::{anyhow, Result};
+use openapiv3::{OpenAPI, PathItem};
+use serde::Deserialize;
+
+use std::io::Read;
+use std::path::Path;
+
+use crate::output::Format;
+use crate::spec::{METHODS, NULL_PATHS_EXTENSION};
+
+/// Read and deserialize the spec at `path`. With `json5`, the file is
+/// parsed as JSON5, which allows comments and trailing commas.
+/// An `http://` or `https://` URL is fetched instead of read from disk.
+/// Files ending in `.yaml` or `.yml` are read as YAML, those ending in
+/// `.json` or `.json5` as JSON, and the format of others is sniffed, see
+/// [`sniff_format`].
+pub fn read_spec(path: &Path, json5: bool) -> Result<OpenAPI> {
+  let content = read_text(path)?;
+  let format = extension_format(path).unwrap_or_else(|| sniff_format(&content));
+  parse_as(&content, format, json5)
+}
+
+/// The text of the file or URL at `path`
+fn read_text(path: &Path) -> Result<String> {
+  let content = match url(path) {
+    Some(url) => fetch(url)?,
+    None => std::fs::read(path)?,
+  };
+  decode(content, &path.display().to_string())
+}
+
+/// Whether `path` is `-`, standing for stdin
+pub fn is_stdin(path: &Path) -> bool {
+  path.as_os_str() == "-"
+}
+
+/// Read and deserialize a spec in `format` from `reader`, like stdin, where
+/// there is no file name to tell the format from. Without a `format`, it is
+/// sniffed, see [`sniff_format`]. `json5` applies to JSON only. `source`
+/// names the stream in errors.
+pub fn read_stream(
+  mut reader: impl Read,
+  format: Option<Format>,
+  json5: bool,
+  source: &str,
+) -> Result<OpenAPI> {
+  let mut content = Vec::new();
+  reader.read_to_end(&mut content)?;
+  let content = decode(content, source)?;
+  let format = format.unwrap_or_else(|| sniff_format(&content));
+  parse_as(&content, format, json5)
+}
+
+/// A line of an NDJSON stream of operations, see [`parse_ndjson`]
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct NdjsonOperation {
+  path: String,
+  method: String,
+  operation: serde_json::Value,
+}
+
+/// Read a stream of operations as newline-delimited JSON from the file,
+/// URL or stdin at `path`, see [`parse_ndjson`]
+pub fn read_ndjson_spec(path: &Path) -> Result<OpenAPI> {
+  if is_stdin(path) {
+    let mut content = Vec::new();
+    std::io::stdin().read_to_end(&mut content)?;
+    parse_ndjson(&decode(content, "stdin")?, "stdin")
+  } else {
+    parse_ndjson(&read_text(path)?, &path.display().to_string())
+  }
+}
+
+/// Assemble a spec from newline-delimited JSON, where every line is an
+/// operation like `{"path": "/users", "method": "get", "operation": {...}}`,
+/// as pipelines emit them that do not deal in whole specs. Blank lines are
+/// skipped. The spec is titled after `source`, which names the stream in
+/// errors too, along with the number of the offending line.
+pub fn parse_ndjson(content: &str, source: &str) -> Result<OpenAPI> {
+  let content = content.strip_prefix('\u{feff}').unwrap_or(content);
+  let mut paths = serde_json::Map::new();
+  for (number, line) in content.lines().enumerate() {
+    if line.trim().is_empty() {
+      continue;
+    }
+    let line_error = |err: &dyn std::fmt::Display| {
+      anyhow!(
+        "invalid operation at line {} of {source}: {err}",
+        number + 1
+      )
+    };
+    let NdjsonOperation {
+      path,
+      method,
+      operation,
+    } = serde_json::from_str(line).map_err(|err| line_error(&err))?;
+    let method = method.to_lowercase();
+    if !METHODS.contains(&method.as_str()) {
+      return Err(line_error(&format!("unknown method {method:?}")));
+    }
+    let item = paths
+      .entry(path.clone())
+      .or_insert_with(|| serde_json::json!({}));
+    if item.get(&method).is_some() {
+      return Err(line_error(&format!(
+        "{} {path} is defined more than once",
+        method.to_uppercase()
+      )));
+    }
+    item[method] = operation;
+  }
+  deserialize_spec(serde_json::json!({
+    "openapi": "3.0.3",
+    "info": { "title": source, "version": "1" },
+    "paths": paths,
+  }))
+}
+
+/// The format the extension of `path` calls for, if it is a known one
+pub fn extension_format(path: &Path) -> Option<Format> {
+  match path.extension()?.to_str()?.to_lowercase().as_str() {
+    "json" | "json5" => Some(Format::Json),
+    "yaml" | "yml" => Some(Format::Yaml),
+    _ => None,
+  }
+}
+
+/// Guess the format of a spec from its first character other than
+/// whitespace or a byte order mark: a JSON document starts with `{` or `[`,
+/// anything else is taken for YAML
+pub fn sniff_format(content: &str) -> Format {
+  match content
+    .trim_start_matches(|c: char| c == '\u{feff}' || c.is_whitespace())
+    .chars()
+    .next()
+  {
+    Some('{' | '[') => Format::Json,
+    _ => Format::Yaml,
+  }
+}
+
+/// Deserialize a spec from its textual representation in `format`
+fn parse_as(content: &str, format: Format, json5: bool) -> Result<OpenAPI> {
+  match format {
+    Format::Json => parse_spec(content, json5),
+    Format::Yaml => {
+      let content = content.strip_prefix('\u{feff}').unwrap_or(content);
+      deserialize_spec(serde_yaml::from_str(content)?)
+    }
+  }
+}
+
+/// Turn the raw bytes of a spec into text, with an explanation of what is
+/// wrong if they are not UTF-8
+fn decode(content: Vec<u8>, source: &str) -> Result<String> {
+  if content.starts_with(&[0xff, 0xfe]) || content.starts_with(&[0xfe, 0xff]) {
+    return Err(anyhow!(
+      "{source} is encoded as UTF-16, convert it to UTF-8 first"
+    ));
+  }
+  String::from_utf8(content).map_err(|err| {
+    anyhow!(
+      "{source} is not valid UTF-8 (invalid byte at offset {}), convert it to \
+       UTF-8 first",
+      err.utf8_error().valid_up_to()
+    )
+  })
+}
+
+/// The URL `path` spells, if it is an HTTP(S) URL rather than a file path
+fn url(path: &Path) -> Option<&str> {
+  path
+    .to_str()
+    .filter(|path| path.starts_with("http://") || path.starts_with("https://"))
+}
+
+/// Whether `path` is fetched over HTTP(S) rather than read from disk
+pub fn is_url(path: &Path) -> bool {
+  url(path).is_some()
+}
+
+/// Whether a response of `content_type` cannot be a spec, like the HTML of
+/// a login page. Specs are served as JSON, YAML or plain text under all
+/// kinds of content types, so only those that clearly are something else
+/// count.
+fn is_no_spec(content_type: &str) -> bool {
+  let essence = content_type
+    .split(';')
+    .next()
+    .unwrap_or_default()
+    .trim()
+    .to_lowercase();
+  matches!(essence.as_str(), "text/html" | "application/xhtml+xml")
+    || ["image/", "audio/", "video/", "font/"]
+      .iter()
+      .any(|kind| essence.starts_with(kind))
+}
+
+/// Download the spec at `url`. Fails unless the server answers with a
+/// success status, and refuses responses that cannot be a spec, see
+/// [`is_no_spec`]. The format is told from the URL or sniffed, like for
+/// files.
+fn fetch(url: &str) -> Result<Vec<u8>> {
+  let response = reqwest::blocking::get(url)?;
+  let status = response.status();
+  if !status.is_success() {
+    return Err(anyhow!("fetching {url} failed: {status}"));
+  }
+  if let Some(content_type) = response
+    .headers()
+    .get(reqwest::header::CONTENT_TYPE)
+    .and_then(|content_type| content_type.to_str().ok())
+    && is_no_spec(content_type)
+  {
+    return Err(anyhow!(
+      "fetching {url} returned {content_type:?} instead of a spec"
+    ));
+  }
+  Ok(response.bytes()?.to_vec())
+}
+
+/// Deserialize a spec from its textual representation. A leading byte
+/// order mark, as some editors on Windows write it, is skipped. Errors
+/// name the JSON path of the offending value.
+pub fn parse_spec(content: &str, json5: bool) -> Result<OpenAPI> {
+  deserialize_spec(parse_value(content, json5)?)
+}
+
+/// Parse a JSON or, with `json5`, JSON5 document, skipping a leading byte
+/// order mark
+pub fn parse_value(content: &str, json5: bool) -> Result<serde_json::Value> {
+  let content = content.strip_prefix('\u{feff}').unwrap_or(content);
+  Ok(if json5 {
+    json5::from_str(content)?
+  } else {
+    serde_json::from_str(content)?
+  })
+}
+
+/// Parse the document `content` read from `path`, as JSON or YAML like
+/// [`read_spec`] tells them apart. `json5` applies to JSON only.
+pub fn parse_document(
+  content: &str,
+  path: &Path,
+  json5: bool,
+) -> Result<serde_json::Value> {
+  match extension_format(path).unwrap_or_else(|| sniff_format(content)) {
+    Format::Json => parse_value(content, json5),
+    Format::Yaml => {
+      let content = content.strip_prefix('\u{feff}').unwrap_or(content);
+      Ok(serde_yaml::from_str(content)?)
+    }
+  }
+}
+
+/// Read the spec at `path` like [`read_spec`], inlining `$ref`s to other
+/// local files first, see [`crate::bundle::bundle`]. The spec and the files
+/// it refers to may be JSON or YAML.
+pub fn read_bundled_spec(path: &Path, json5: bool) -> Result<OpenAPI> {
+  if is_url(path) {
+    return Err(anyhow!("--bundle requires a local --input file"));
+  }
+  let content = decode(std::fs::read(path)?, &path.display().to_string())?;
+  let mut value = parse_document(&content, path, json5)?;
+  crate::bundle::bundle(&mut value, path, json5)?;
+  deserialize_spec(value)
+}
+
+/// Deserialize a spec from its parsed representation. Errors name the JSON
+/// path of the offending value. Path items that are `null`, as hand edits
+/// leave them behind, are removed and their paths recorded in
+/// [`NULL_PATHS_EXTENSION`], for [`crate::validate::skip_null_path_items`]
+/// to report.
+fn deserialize_spec(mut value: serde_json::Value) -> Result<OpenAPI> {
+  if let Some(paths) = value
+    .get_mut("paths")
+    .and_then(serde_json::Value::as_object_mut)
+  {
+    let null_paths: Vec<String> = paths
+      .iter()
+      .filter(|(_, item)| item.is_null())
+      .map(|(path, _)| path.clone())
+      .collect();
+    paths.retain(|_, item| !item.is_null());
+    if !null_paths.is_empty()
+      && let Some(root) = value.as_object_mut()
+    {
+      root.insert(NULL_PATHS_EXTENSION.to_string(), null_paths.into());
+    }
+  }
+  serde_path_to_error::deserialize(&value).map_err(|err| {
+    let location = locate_path_item_error(&value)
+      .unwrap_or_else(|| format!("{}: {}", err.path(), err.inner()));
+    anyhow!("invalid spec at {location}")
+  })
+}
+
+/// Find the path item that fails to deserialize, and the location of the
+/// problem within it. This is needed since openapiv3 buffers the whole
+/// `paths` object while deserializing it, so errors in there otherwise
+/// only point at `paths`.
+fn locate_path_item_error(spec: &serde_json::Value) -> Option<String> {
+  spec
+    .get("paths")?
+    .as_object()?
+    .iter()
+    .filter(|(_, item)| item.get("$ref").is_none())
+    .find_map(|(path, item)| {
+      let err = serde_path_to_error::deserialize::<_, PathItem>(item).err()?;
+      Some(format!("paths.{path}.{}: {}", err.path(), err.inner()))
+    })
+}
+
+#[test]
+fn test_is_url() {
+  assert!(is_url(Path::new("https://example.com/swagger/v4.json")));
+  assert!(is_url(Path::new("http://localhost:8080/v4.json")));
+  assert!(!is_url(Path::new("./generator/swagger/v4.json")));
+  assert!(!is_url(Path::new("http.json")));
+}
+
+#[test]
+fn test_is_no_spec() {
+  assert!(is_no_spec("text/html; charset=utf-8"));
+  assert!(is_no_spec("image/png"));
+  assert!(!is_no_spec("application/json"));
+  assert!(!is_no_spec("application/yaml"));
+  assert!(!is_no_spec("text/plain; charset=utf-8"));
+  assert!(!is_no_spec("application/octet-stream"));
+}
+
+#[test]
+fn test_byte_order_mark() {
+  let content = "\u{feff}{
+    \"openapi\": \"3.0.1\",
+    \"info\": { \"title\": \"test\", \"version\": \"1\" },
+    \"paths\": { \"/users\": { \"get\": { \"responses\": {} } } }
+  }";
+  let spec = parse_spec(&decode(content.into(), "test").unwrap(), false).unwrap();
+  assert!(spec.paths.paths.contains_key("/users"));
+  assert!(parse_spec(content, true).is_ok());
+
+  let err = decode(vec![b'{', 0xc3, b'}'], "test").unwrap_err();
+  assert!(err.to_string().contains("offset 1"));
+  assert!(decode(vec![0xff, 0xfe, b'{', 0], "test").is_err());
+}
+
+#[test]
+fn test_parse_error_location() {
+  let content = r#"{
+    "openapi": "3.0.1",
+    "info": { "title": "test", "version": "1" },
+    "paths": {
+      "/users": {
+        "get": { "tags": "users", "responses": {} }
+      }
+    }
+  }"#;
+  let err = parse_spec(content, false).unwrap_err().to_string();
+  assert!(
+    err.starts_with("invalid spec at paths./users.get.tags: invalid type"),
+    "{err}"
+  );
+  assert!(err.contains("expected a sequence"), "{err}");
+
+  let err = parse_spec(content, true).unwrap_err().to_string();
+  assert!(
+    err.starts_with("invalid spec at paths./users.get.tags: invalid type"),
+    "{err}"
+  );
+}
+
+#[test]
+fn test_parse_json5() {
+  let content = r#"{
+    // generated by hand
+    openapi: "3.0.1",
+    info: { title: "test", version: "1", },
+    paths: {
+      /* no operation id yet */
+      "/users": { get: { responses: {}, }, },
+    },
+  }"#;
+  assert!(parse_spec(content, false).is_err());
+
+  let spec = parse_spec(content, true).unwrap();
+  let item = spec.paths.paths["/users"].as_item().unwrap();
+  assert!(item.get.is_some());
+}
+
+#[test]
+fn test_sniff_format() {
+  assert_eq!(sniff_format("{\"openapi\": \"3.0.1\"}"), Format::Json);
+  assert_eq!(sniff_format("\u{feff}\n  [1]"), Format::Json);
+  assert_eq!(sniff_format("openapi: 3.0.1\n"), Format::Yaml);
+  assert_eq!(sniff_format("# comment\n{}"), Format::Yaml);
+  assert_eq!(sniff_format(""), Format::Yaml);
+
+  let json = r#"{
+    "openapi": "3.0.1",
+    "info": { "title": "test", "version": "1" },
+    "paths": { "/users": { "get": { "responses": {} } } }
+  }"#;
+  let yaml = "openapi: 3.0.1
+info: { title: test, version: '1' }
+paths:
+  /users:
+    get: { responses: {} }
+";
+  for content in [json, yaml] {
+    let spec = read_stream(content.as_bytes(), None, false, "stdin").unwrap();
+    assert!(spec.paths.paths.contains_key("/users"));
+  }
+  // an explicit format wins over sniffing
+  assert!(read_stream(json.as_bytes(), Some(Format::Json), false, "stdin").is_ok());
+  assert!(read_stream(yaml.as_bytes(), Some(Format::Json), false, "stdin").is_err());
+  assert_eq!(extension_format(Path::new("api.YML")), Some(Format::Yaml));
+  assert_eq!(extension_format(Path::new("api")), None);
+}
+
+#[test]
+fn test_parse_ndjson() {
+  let content = r#"{"path": "/users", "method": "get", "operation": {"responses": {}}}
+
+{"path": "/users", "method": "POST", "operation": {"operationId": "create_user", "responses": {}}}
+{"path": "/groups", "method": "get", "operation": {"responses": {}}}
+"#;
+  let mut spec = parse_ndjson(content, "operations.ndjson").unwrap();
+  assert_eq!(spec.info.title, "operations.ndjson");
+  crate::generate::gen_operation_ids(&mut spec, &Default::default()).unwrap();
+  assert_eq!(
+    crate::generate::collect_opids(&spec)
+      .into_iter()
+      .map(|((path, method), opid)| format!("{method} {path} {}", opid.unwrap()))
+      .collect::<Vec<_>>(),
+    [
+      "get /groups groups_get",
+      "get /users users_get",
+      "post /users create_user"
+    ]
+  );
+
+  let err = parse_ndjson("\n{\"path\": \"/users\"", "stdin").unwrap_err();
+  assert!(
+    err
+      .to_string()
+      .starts_with("invalid operation at line 2 of stdin"),
+    "{err}"
+  );
+  let err = parse_ndjson(
+    r#"{"path": "/users", "method": "fetch", "operation": {}}"#,
+    "stdin",
+  )
+  .unwrap_err();
+  assert!(err.to_string().contains("unknown method"), "{err}");
+  let line = r#"{"path": "/users", "method": "get", "operation": {"responses": {}}}"#;
+  let err = parse_ndjson(&format!("{line}\n{line}"), "stdin").unwrap_err();
+  assert!(err.to_string().contains("line 2"), "{err}");
+  assert!(err.to_string().contains("more than once"), "{err}");
+}
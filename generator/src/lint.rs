@@ -0,0 +1,259 @@
+use anyhow::{anyhow, Result};
+use openapiv3::OpenAPI;
+
+use std::collections::BTreeMap;
+use std::fmt;
+
+use crate::cli::LintArgs;
+use crate::input::read_spec;
+use crate::opid::casing_of;
+
+/// How bad a lint is
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, clap::ValueEnum)]
+pub enum Severity {
+  /// Worth knowing, but fine as it is
+  Info,
+  /// Likely to make the generated client awkward to use
+  Warning,
+  /// Breaks the generated client
+  Error,
+}
+
+impl fmt::Display for Severity {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    f.write_str(match self {
+      Severity::Info => "info",
+      Severity::Warning => "warning",
+      Severity::Error => "error",
+    })
+  }
+}
+
+/// A consistency issue of an explicit operation ID
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Lint {
+  pub operation_id: String,
+  pub severity: Severity,
+  pub message: String,
+}
+
+impl fmt::Display for Lint {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(
+      f,
+      "{}: {}: {}",
+      self.severity, self.operation_id, self.message
+    )
+  }
+}
+
+/// Check the explicit operation IDs of `spec` for consistency, sorted by
+/// operation ID, the most severe lints first:
+///
+/// - characters that do not make for an identifier, or a leading digit,
+///   are errors
+/// - operation IDs used by several operations are errors
+/// - operation IDs only differing in case, `_` or `-` are errors, since
+///   the generated method names would collide
+/// - casing styles other than the one most operation IDs use are warnings,
+///   see [`crate::opid::OperationIds::casing_mix`]. Single lowercase words
+///   fit any style.
+/// - operation IDs longer than `max_length` are warnings
+pub fn lint(spec: &OpenAPI, max_length: usize) -> Vec<Lint> {
+  let mut operation_ids: BTreeMap<&str, usize> = BTreeMap::new();
+  spec
+    .paths
+    .paths
+    .values()
+    .filter_map(|item| item.as_item())
+    .flat_map(|item| item.iter())
+    .filter_map(|(_, op)| op.operation_id.as_deref())
+    .for_each(|opid| *operation_ids.entry(opid).or_default() += 1);
+  let mut lints = Vec::new();
+  let mut push = |operation_id: &str, severity, message: String| {
+    lints.push(Lint {
+      operation_id: operation_id.to_string(),
+      severity,
+      message,
+    });
+  };
+
+  let mut folded: BTreeMap<String, Vec<&str>> = BTreeMap::new();
+  let mut styles: BTreeMap<&str, usize> = BTreeMap::new();
+  for &opid in operation_ids.keys() {
+    folded
+      .entry(opid.to_lowercase().replace(['_', '-'], ""))
+      .or_default()
+      .push(opid);
+    *styles.entry(casing_of(opid)).or_default() += 1;
+  }
+  // ties go to the style sorting first, so the result is stable
+  let prevailing = styles
+    .iter()
+    .filter(|(style, _)| !matches!(**style, "lowercase" | "mixed"))
+    .max_by(|(a, a_count), (b, b_count)| a_count.cmp(b_count).then(b.cmp(a)))
+    .map(|(style, _)| *style);
+
+  for (&opid, &count) in &operation_ids {
+    if opid.starts_with(|c: char| c.is_ascii_digit())
+      || opid.contains(|c: char| !c.is_ascii_alphanumeric() && c != '_')
+    {
+      push(
+        opid,
+        Severity::Error,
+        "is no valid identifier, use only letters, digits and `_`, and no leading digit"
+          .to_string(),
+      );
+    }
+
+    if count > 1 {
+      push(
+        opid,
+        Severity::Error,
+        format!("is used by {count} operations"),
+      );
+    }
+
+    let alike = &folded[&opid.to_lowercase().replace(['_', '-'], "")];
+    if alike.len() > 1 {
+      let others = alike
+        .iter()
+        .filter(|other| *other != opid)
+        .copied()
+        .collect::<Vec<_>>();
+      push(
+        opid,
+        Severity::Error,
+        format!(
+          "only differs from {} in case or separators",
+          others.join(", ")
+        ),
+      );
+    }
+
+    match (casing_of(opid), prevailing) {
+      ("mixed", _) => push(opid, Severity::Warning, "mixes casing styles".to_string()),
+      ("lowercase", _) | (_, None) => {}
+      (style, Some(prevailing)) if style != prevailing => push(
+        opid,
+        Severity::Warning,
+        format!("is {style} while most operation ids are {prevailing}"),
+      ),
+      _ => {}
+    }
+
+    let length = opid.chars().count();
+    if length > max_length {
+      push(
+        opid,
+        Severity::Warning,
+        format!("is {length} characters long, more than the recommended {max_length}"),
+      );
+    }
+  }
+  lints.sort_by(|a, b| (&a.operation_id, b.severity).cmp(&(&b.operation_id, a.severity)));
+  lints
+}
+
+/// Run the `lint` command
+pub fn run(args: &LintArgs) -> Result<()> {
+  let spec = read_spec(&args.spec, args.json5)?;
+  let lints = lint(&spec, args.max_length);
+  lints.iter().for_each(|lint| println!("{lint}"));
+  let failing = lints
+    .iter()
+    .filter(|lint| lint.severity >= args.fail_on)
+    .count();
+  if failing > 0 {
+    return Err(anyhow!(
+      "{failing} operation id lints at or above {}",
+      args.fail_on
+    ));
+  }
+  Ok(())
+}
+
+#[cfg(test)]
+fn lints_of(operation_ids: &[&str], max_length: usize) -> Vec<String> {
+  let paths = operation_ids
+    .iter()
+    .enumerate()
+    .map(|(i, opid)| {
+      format!(
+        r#""/path{i}": {{ "get": {{ "operationId": "{opid}", "responses": {{}} }} }}"#
+      )
+    })
+    .collect::<Vec<_>>()
+    .join(", ");
+  lint(&crate::spec::mk_spec(&format!("{{ {paths} }}")), max_length)
+    .iter()
+    .map(Lint::to_string)
+    .collect()
+}
+
+#[test]
+fn test_lint_identifiers() {
+  assert_eq!(
+    lints_of(&["list_users", "users.get", "2fa_setup"], 64),
+    [
+      "error: 2fa_setup: is no valid identifier, use only letters, digits and `_`, \
+       and no leading digit",
+      "error: users.get: is no valid identifier, use only letters, digits and `_`, \
+       and no leading digit",
+    ]
+  );
+}
+
+#[test]
+fn test_lint_duplicates() {
+  assert_eq!(
+    lints_of(&["list_users", "list_users", "list_groups"], 64),
+    ["error: list_users: is used by 2 operations"]
+  );
+}
+
+#[test]
+fn test_lint_case_folding() {
+  assert_eq!(
+    lints_of(&["get_users", "getUsers", "list_groups"], 64),
+    [
+      "error: getUsers: only differs from get_users in case or separators",
+      "warning: getUsers: is camelCase while most operation ids are snake_case",
+      "error: get_users: only differs from getUsers in case or separators",
+    ]
+  );
+}
+
+#[test]
+fn test_lint_casing_mix() {
+  assert_eq!(
+    lints_of(
+      &[
+        "getUsers",
+        "createUser",
+        "delete_user",
+        "ping",
+        "get_Groups"
+      ],
+      64
+    ),
+    [
+      "warning: delete_user: is snake_case while most operation ids are camelCase",
+      "warning: get_Groups: mixes casing styles",
+    ]
+  );
+  // a single style is consistent
+  assert!(lints_of(&["get_users", "ping"], 64).is_empty());
+}
+
+#[test]
+fn test_lint_length() {
+  assert_eq!(
+    lints_of(&["list_users", "list_all_the_users"], 12),
+    ["warning: list_all_the_users: is 18 characters long, more than the recommended 12"]
+  );
+  // characters are counted, not bytes
+  assert!(lints_of(&["größe_ändern"], 12)
+    .iter()
+    .all(|lint| !lint.contains("characters long")));
+}
@@ -1,371 +1,346 @@
 use anyhow::{anyhow, Result};
+use clap::{ArgMatches, CommandFactory, FromArgMatches};
 use openapiv3::OpenAPI;
 
-use std::collections::BTreeMap;
-
-// Progenitor requires Operation ID to be specified for every operation
-// on every path. This is "holier than thou" compared to the OpenAPI spec
-// The code below creates operation IDs from paths, methods and optionally
-// path parameters
-// The bulk of this code is stolen from
-// https://github.com/upachler/progenitor/blob/1105-progenitor-requires-operationid-to-be-set/progenitor-impl/src/opid.rs
-//
-//
-/// newtype for encapsulating the combination of path and method,
-/// which can uniquely identify a HTTP endpoint. The struct is
-/// designed to be used as a key for map implementations
-#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
-struct PathMethod {
-  path: String,
-  method: String,
-  params: Option<Vec<String>>,
-}
-
-impl PathMethod {
-  /// Create new PathMethod. This may fail if path or method
-  /// are empty.
-  pub fn new(path: &str, method: &str, params: Option<Vec<&str>>) -> Result<Self> {
-    // disallow empty path/method
-    if path.is_empty() || method.is_empty() {
-      return Err(anyhow!("path and method may not be empty",));
-    }
-
-    // NOTE: In the future, we may consider checking for the proper URL path
-    // format in the the future according to the RFC:
-    // https://datatracker.ietf.org/doc/html/rfc3986#section-3.3
-
-    Ok(Self {
-      path: path.to_string(),
-      method: method.to_string(),
-      params: params.map(|v| v.iter().map(|s| s.to_string()).collect()),
-    })
+mod batch;
+mod bundle;
+mod cli;
+#[cfg(feature = "codegen")]
+mod codegen;
+mod config;
+mod diff;
+mod explain;
+mod export;
+mod generate;
+mod input;
+mod lint;
+mod mapping;
+mod merge;
+mod normalize;
+mod opid;
+mod output;
+mod selftest;
+mod settings;
+mod spec;
+mod split;
+mod stats;
+mod strategy;
+mod validate;
+mod warning;
+mod watch;
+
+use cli::{Cli, Command};
+use generate::gen_operation_ids;
+use opid::OperationIds;
+use settings::Settings;
+use warning::Warning;
+
+/// Read the input spec, or merge the specs to merge, and sanity check it.
+/// The warnings printed along the way are counted into `printed`.
+fn read_input(cli: &Cli, printed: &mut usize) -> Result<OpenAPI> {
+  let mut spec = if !cli.merge.is_empty() {
+    let specs = cli
+      .merge
+      .iter()
+      .map(|path| {
+        let mut spec = input::read_spec(path, cli.json5)?;
+        print_warnings(
+          cli,
+          printed,
+          &validate::skip_null_path_items(&mut spec, cli.strict)?,
+        );
+        Ok((path.display().to_string(), spec))
+      })
+      .collect::<Result<Vec<_>>>()?;
+    merge::merge_specs(specs)?
+  } else if cli.ndjson {
+    input::read_ndjson_spec(&cli.input)?
+  } else if cli.bundle {
+    input::read_bundled_spec(&cli.input, cli.json5)?
+  } else if input::is_stdin(&cli.input) {
+    input::read_stream(std::io::stdin(), cli.stdin_format, cli.json5, "stdin")?
+  } else {
+    input::read_spec(&cli.input, cli.json5)?
+  };
+  print_warnings(cli, printed, &validate::check_spec(&mut spec, cli.strict)?);
+  if let Some(max_depth) = cli.max_path_depth {
+    validate::check_path_depth(&spec, max_depth)?;
   }
+  if cli.exclude_internal {
+    spec::remove_internal(&mut spec);
+  }
+  print_warnings(cli, printed, &validate::check_path_params(&spec));
+  if cli.prune_empty {
+    validate::prune_empty_path_items(&mut spec)
+      .iter()
+      .for_each(|path| eprintln!("removed path without operations: {path}"));
+  }
+  if cli.strict {
+    validate::reject_empty_path_items(&spec)?;
+  } else {
+    print_warnings(cli, printed, &validate::warn_empty_path_items(&spec));
+  }
+  Ok(spec)
 }
 
-/// Store for a one to one mapping between OAS operation IDs and
-/// path/method pairs. The store
-/// supports lookup in each direction.
-#[derive(Default, Debug)]
-pub struct OperationIds {
-  opid_to_path_method: BTreeMap<String, PathMethod>,
-  path_method_to_opid: BTreeMap<PathMethod, String>,
-}
-
-/// Extract path parameters (like {id}) from a path string
-/// and remove them from the path string. If parameters were found,
-/// return a vector of them and the modified path. Otherwise, [`None`]
-fn extract_params(path: &str) -> Option<(Vec<&str>, String)> {
-  let mut params = Vec::new();
-  let mut clean_path = String::with_capacity(path.len());
-  let mut last_end = 0;
-  let mut in_param = false;
-  let mut param_start = 0;
-
-  for (i, c) in path.char_indices() {
-    if c == '{' && !in_param {
-      in_param = true;
-      clean_path.push_str(&path[last_end..i]);
-      clean_path.push_str("{}");
-      param_start = i + 1;
-    } else if c == '}' && in_param {
-      in_param = false;
-      if i > param_start {
-        params.push(&path[param_start..i]);
+/// Read the input, assign operation IDs and write the output file.
+/// Returns the assigned operation IDs, warnings are counted into `printed`.
+fn generate(
+  cli: &Cli,
+  settings: &Settings,
+  overwrite: bool,
+  printed: &mut usize,
+) -> Result<OperationIds> {
+  let mut spec = read_input(cli, printed)?;
+  if !cli.mapping_only {
+    output::check_output(&cli.output, overwrite)?;
+  }
+  let opids = match &cli.append_mapping {
+    Some(path) => {
+      let mut existing = mapping::read_mapping(path)?;
+      if let Some(paths) = &cli.only_paths {
+        existing = mapping::forget_paths(&spec, existing, &mapping::read_paths(paths)?);
       }
-      last_end = i + 1;
+      let (opids, appended) = mapping::append_to_mapping(&mut spec, settings, existing)?;
+      mapping::write_mapping(path, &appended)?;
+      opids
     }
+    None => gen_operation_ids(&mut spec, settings)?,
+  };
+  if cli.drop_skipped_methods {
+    spec::remove_methods(&mut spec, &settings.skip_methods);
   }
-
-  // Add any remaining part after the last parameter
-  if last_end < path.len() {
-    clean_path.push_str(&path[last_end..]);
+  if cli.tag_stats {
+    eprint!("{}", stats::format_tag_counts(&stats::tag_counts(&spec)));
   }
-
-  if params.is_empty() {
-    None
-  } else {
-    Some((params, clean_path))
+  if let Some(path) = &cli.synthetic_only_out {
+    let trimmed = split::synthetic_only(&spec, &opids);
+    output::write_output(path, overwrite, |writer| {
+      output::write_spec(writer, &trimmed, cli.compact)
+    })?;
+  }
+  if let Some(path) = &cli.mapping_out {
+    mapping::write_mapping(path, &mapping::mapping_of(&opids, cli.mapping_key))?;
+  }
+  if let Some(path) = &cli.csv_out {
+    mapping::write_csv(path, &opids)?;
   }
+  if let Some(path) = &cli.enum_out {
+    export::write_typescript_enum(path, &opids)?;
+  }
+  if let Some(path) = &cli.rust_enum_out {
+    export::write_rust_enum(path, &opids)?;
+  }
+  if !cli.mapping_only {
+    output::write_output(&cli.output, overwrite, |writer| {
+      let format = output::output_format(&cli.output, cli.stdout_format);
+      output::write_spec_as(writer, &spec, format, cli.compact)
+    })?;
+  }
+  #[cfg(feature = "codegen")]
+  if let Some(path) = &cli.codegen.codegen_out {
+    codegen::write_client(&spec, &cli.codegen, path)?;
+  }
+  print_warnings(cli, printed, opids.warnings());
+  Ok(opids)
 }
 
-impl OperationIds {
-  /// Find operation ID for given path and method. Returns [`None`] if
-  /// no operation ID was found
-  pub fn opid_for_path_method(&self, path: &str, method: &str) -> Option<&str> {
-    let key = match extract_params(path) {
-      Some((params, normalized_path)) => {
-        match PathMethod::new(&normalized_path, method, Some(params)) {
-          Ok(path_method) => path_method,
-          Err(_) => return None,
-        }
-      }
-      None => match PathMethod::new(path, method, None) {
-        Ok(path_method) => path_method,
-        Err(_) => return None,
-      },
-    };
-
-    self.path_method_to_opid.get(&key).map(|s| s.as_str())
-  }
+/// Print warnings about the input spec in the format the command line asks
+/// for, and count them into `printed`
+fn print_warnings(cli: &Cli, printed: &mut usize, warnings: &[Warning]) {
+  let format = cli.warning_format();
+  *printed += warnings.len();
+  warnings
+    .iter()
+    .for_each(|warning| eprintln!("{}", format.format(warning, &cli.input)));
+}
 
-  /// Find path and method for a given operation ID. Returns [`None`] if
-  /// no path/method combination was found for the given operation ID
-  pub fn path_method_for_opid(&self, operation_id: &str) -> Option<(&str, &str)> {
-    self
-      .opid_to_path_method
-      .get(operation_id)
-      .map(|path_method| (path_method.path.as_str(), path_method.method.as_str()))
+/// Fail if any of the `printed` warnings was printed and
+/// `--exit-code-on-warnings` is given. Called once everything is written,
+/// so the output is there to look at.
+fn fail_on_warnings(cli: &Cli, printed: usize) -> Result<()> {
+  if cli.exit_code_on_warnings && printed > 0 {
+    return Err(anyhow!(
+      "{printed} warnings were printed, failing as --exit-code-on-warnings asks"
+    ));
   }
+  Ok(())
+}
 
-  /// Generate a new operation ID candidate for the given PathMethod, considering
-  /// the number of attempts that have already been made. The number of attempts
-  /// is included in the candiate name (unless it is 0), to help resolve name
-  /// collisions.
-  /// For generated operation IDs that would start with a number,
-  /// the character 'n' is prepended.
-  ///
-  /// The operation_id names are created with the pattern
-  /// `converted_path [attempt] converted_method`.
-  ///
-  /// For `GET /foo/bar`, this will yield an operation ID of `foo_bar_get`.
-  /// It was deliberately chosen to have the method part at the end of the
-  /// generated operation ID string, so that the main point of destinction
-  /// is the path.
-  /// This is useful when the operation ID is used to
-  /// generate client method names: `foo_bar_get` and `foo_bar_post` will
-  /// be listed next to each other in a method name list.
-  fn gen_operation_id(path_method: &PathMethod, attempt: u32) -> String {
-    let mut opid: String = path_method
-      .path
-      .replace(|c: char| !c.is_alphanumeric(), "_")
-      .trim_matches('_')
-      .to_lowercase();
-    if opid.starts_with(char::is_numeric) {
-      opid.insert(0, 'n');
-    }
+fn main() -> Result<()> {
+  let matches = Cli::command().get_matches();
+  let cli = Cli::from_arg_matches(&matches).unwrap_or_else(|err| err.exit());
+  let printed = run(&cli, &matches)?;
+  fail_on_warnings(&cli, printed)
+}
 
-    let m = path_method.method.to_lowercase();
-    if attempt == 0 {
-      opid += &format!("_{m}");
-    } else {
-      opid += &format!("{attempt}_{m}");
+/// Do what the command line parsed into `cli` out of `matches` asks for.
+/// Returns how many warnings were printed.
+fn run(cli: &Cli, matches: &ArgMatches) -> Result<usize> {
+  if cli.selftest {
+    return selftest::run().map(|()| 0);
+  }
+  if let Some(command) = &cli.command {
+    let sub_matches = matches.subcommand().map_or(matches, |(_, sub)| sub);
+    let settings =
+      |args: &cli::SettingsArgs| config::layered_settings(&cli.config, args, sub_matches);
+    let result = match command {
+      Command::Diff(args) => diff::run(args, &settings(&args.settings)?),
+      Command::Explain(args) => explain::run(args, &settings(&args.settings)?),
+      Command::Lint(args) => lint::run(args),
+      Command::Normalize(args) => normalize::run(args),
+      Command::ValidateMapping(args) => mapping::run_validate(args),
     };
-
-    if let Some(params) = &path_method.params {
-      params.iter().for_each(|p| {
-        opid += &format!("_by_{}", p.to_lowercase());
-      });
-    }
-    opid
+    return result.map(|()| 0);
   }
 
-  /// Insert a new operation ID with with it's path and method attached.
-  /// The method will fail if the operation ID, or the path and method
-  /// combination already exist in this [`OperationIds`] instance.
-  pub fn insert_opid_with_path_method(
-    &mut self,
-    operation_id: &str,
-    path: &str,
-    method: &str,
-  ) -> Result<()> {
-    let key = match extract_params(path) {
-      Some((params, normalized_path)) => {
-        PathMethod::new(&normalized_path, method, Some(params))?
-      }
-      None => PathMethod::new(path, method, None)?,
-    };
+  // config files in order, then the flags given on the command line
+  let mut settings = config::layered_settings(&cli.config, &cli.settings, matches)?;
+  settings.write_back = !cli.mapping_only;
+  let mut printed = 0;
 
-    if self.opid_to_path_method.contains_key(operation_id) {
-      return Err(anyhow!("operation id is already present: {operation_id:?}"));
-    }
-    if self.path_method_to_opid.contains_key(&key) {
-      return Err(anyhow!(
-        "the combination of path {} and method {} is already present",
-        key.path,
-        key.method
-      ));
-    }
+  if cli.dump_schema {
+    println!("{}", serde_json::to_string_pretty(&config::schema())?);
+    return Ok(printed);
+  }
+  if cli.print_config {
+    eprintln!("{}", serde_json::to_string_pretty(&settings)?);
+    return Ok(printed);
+  }
 
-    self
-      .opid_to_path_method
-      .insert(operation_id.to_string(), key.clone());
-    self
-      .path_method_to_opid
-      .insert(key, operation_id.to_string());
-    Ok(())
+  if let Some(count) = cli.preview {
+    let spec = read_input(cli, &mut printed)?;
+    generate::preview(&spec, &settings, count)?
+      .iter()
+      .for_each(|opid| println!("{opid}"));
+    return Ok(printed);
   }
 
-  /// Insert a generated opid for the given path and method combination.
-  /// The method will choose an operation ID that does not collide
-  /// with pre existing operation IDs in this [`OperationIds`] instance.
-  /// The method will fail if the given path and methoc combination already
-  /// exists.
-  /// Returns synthetic operation ID
-  pub fn insert_synthetic_opid_for_path_method(
-    &mut self,
-    path: &str,
-    method: &str,
-  ) -> Result<String> {
-    let key = match extract_params(path) {
-      Some((params, normalized_path)) => {
-        PathMethod::new(&normalized_path, method, Some(params))?
-      }
-      None => PathMethod::new(path, method, None)?,
+  if let Some(path) = &cli.check_stability {
+    let mut spec = read_input(cli, &mut printed)?;
+    let settings = Settings {
+      write_back: false,
+      ..settings
     };
+    let opids = gen_operation_ids(&mut spec, &settings)?;
+    return mapping::check_stability(&opids, path).map(|()| printed);
+  }
 
-    if self.path_method_to_opid.contains_key(&key) {
-      return Err(anyhow!("operation id is already present: {key:?}"));
+  if cli.watch {
+    if input::is_url(&cli.input) || input::is_stdin(&cli.input) {
+      return Err(anyhow!("--watch requires a local --input file"));
     }
+    watch::watch(&cli.input, || {
+      let opids = generate(cli, &settings, true, &mut printed)?;
+      eprintln!("wrote {}: {}", cli.output.display(), opids.stats());
+      Ok(())
+    })?;
+    return Ok(printed);
+  }
 
-    let mut candidate;
-    let mut attempt = 0;
+  let batch_options = batch::BatchOptions {
+    json5: cli.json5,
+    settings: &settings,
+    compact: cli.compact,
+    overwrite: cli.overwrite,
+    strict: cli.strict,
+    warning_format: cli.warning_format(),
+  };
+  if !cli.batch.is_empty() {
+    let out_dir = cli
+      .out_dir
+      .as_ref()
+      .ok_or_else(|| anyhow!("--batch requires --out-dir"))?;
+    return batch::run(&cli.batch, out_dir, &batch_options);
+  }
+  if let Some(dir) = &cli.recursive {
+    return batch::run_recursive(dir, cli.out_dir.as_deref(), &batch_options);
+  }
 
-    loop {
-      candidate = Self::gen_operation_id(&key, attempt);
-      attempt += 1;
-      if !self.opid_to_path_method.contains_key(&candidate) {
-        break;
-      }
+  if cli.split_by_tag {
+    let out_dir = cli
+      .out_dir
+      .as_ref()
+      .ok_or_else(|| anyhow!("--split-by-tag requires --out-dir"))?;
+    let spec = read_input(cli, &mut printed)?;
+    let sub_specs = split::split_by_tag(&spec);
+    let file_names = split::file_names(sub_specs.keys())?;
+    std::fs::create_dir_all(out_dir)?;
+    for (tag, mut sub_spec) in sub_specs {
+      let opids = gen_operation_ids(&mut sub_spec, &settings)?;
+      print_warnings(cli, &mut printed, opids.warnings());
+      output::write_output(&out_dir.join(&file_names[&tag]), cli.overwrite, |writer| {
+        output::write_spec(writer, &sub_spec, cli.compact)
+      })?;
     }
-
-    self
-      .path_method_to_opid
-      .insert(key.clone(), candidate.clone());
-    self.opid_to_path_method.insert(candidate.clone(), key);
-    Ok(candidate)
+    return Ok(printed);
   }
-}
-
-#[cfg(test)]
-fn mk_pm(path: &str, method: &str) -> PathMethod {
-  PathMethod::new(path, method, None).unwrap()
-}
-
-#[test]
-fn test_extract_params() {
-  assert_eq!(extract_params("/foo/bar"), None);
-  assert_eq!(
-    extract_params("/foo/{bar}/baz"),
-    Some((vec!["bar"], String::from("/foo/{}/baz")))
-  );
-  assert_eq!(
-    extract_params("/foo/{bar}/baz/{quux}"),
-    Some((vec!["bar", "quux"], String::from("/foo/{}/baz/{}")))
-  );
-  assert_eq!(
-    extract_params("/{foo}/{bar}"),
-    Some((vec!["foo", "bar"], String::from("/{}/{}")))
-  );
-}
 
-#[test]
-fn test_operation_id_generation() {
-  assert_eq!(
-    OperationIds::gen_operation_id(&mk_pm("/foo/bar", "get"), 0),
-    "foo_bar_get"
-  );
-  assert_eq!(
-    OperationIds::gen_operation_id(&mk_pm("/foo/bar", "get"), 1),
-    "foo_bar1_get"
-  );
-  assert_eq!(
-    OperationIds::gen_operation_id(&mk_pm("/some.json", "get"), 0),
-    "some_json_get"
-  );
+  let opids = generate(cli, &settings, cli.overwrite, &mut printed)?;
+  if cli.stats {
+    eprintln!("{}", opids.stats());
+  }
+  if let Some(path) = &cli.stats_out {
+    stats::write_stats(path, &opids.stats())?;
+  }
+  if cli.operation_id_length_histogram {
+    eprint!("{}", opids.length_histogram());
+  }
+  if cli.dump {
+    eprint!("{}", opids.dump());
+  }
+  if cli.report {
+    print!("{}", opids.report());
+  }
+  if cli.report_grouped {
+    print!("{}", opids.report_grouped());
+  }
+  if cli.report_casing_mix {
+    print!("{}", opids.casing_mix());
+  }
+  Ok(printed)
 }
 
 #[test]
-fn test_operation_ids() {
-  let mut opids = OperationIds::default();
-
-  // insert
-  opids
-    .insert_opid_with_path_method("foo_get", "/foo", "get")
-    .unwrap();
-  assert_eq!(opids.opid_for_path_method("/foo", "get"), Some("foo_get"));
-  assert_eq!(opids.path_method_for_opid("foo_get"), Some(("/foo", "get")));
-  opids
-    .insert_opid_with_path_method("foo_post", "/foo", "post")
-    .unwrap();
-  assert_eq!(opids.opid_for_path_method("/foo", "post"), Some("foo_post"));
-  assert_eq!(
-    opids.path_method_for_opid("foo_post"),
-    Some(("/foo", "post"))
-  );
-
-  // insert must fail because of collision with operation id
-  assert!(opids
-    .insert_opid_with_path_method("foo_get", "/bar", "get")
-    .is_err());
-
-  // insert must fail because of collision with path and method
-  assert!(opids
-    .insert_opid_with_path_method("bar_get", "/foo", "get")
-    .is_err());
-
-  // now check we can create synthetic operation ids:
-  assert!(opids
-    .insert_synthetic_opid_for_path_method("/bar", "get")
-    .is_ok());
-  assert_eq!(opids.opid_for_path_method("/bar", "get"), Some("bar_get"));
-  assert_eq!(opids.path_method_for_opid("bar_get"), Some(("/bar", "get")));
-  assert!(opids
-    .insert_synthetic_opid_for_path_method("/bar", "post")
-    .is_ok());
-  assert_eq!(opids.opid_for_path_method("/bar", "post"), Some("bar_post"));
-  assert_eq!(
-    opids.path_method_for_opid("bar_post"),
-    Some(("/bar", "post"))
-  );
-
-  // test collisions.
-  // we're going to collide with foo_bar_get
-  opids
-    .insert_opid_with_path_method("foo_bar_get", "/foobar", "get")
-    .unwrap();
-  opids
-    .insert_synthetic_opid_for_path_method("/foo/bar", "get")
-    .unwrap();
-  assert_eq!(
-    opids.opid_for_path_method("/foo/bar", "get"),
-    Some("foo_bar1_get")
-  );
-  assert_eq!(
-    opids.path_method_for_opid("foo_bar1_get"),
-    Some(("/foo/bar", "get"))
-  );
-}
-
-fn gen_operation_ids(spec: &mut OpenAPI) -> Result<()> {
-  let mut opids = OperationIds::default();
-
-  spec
-    .paths
-    .paths
-    .iter_mut()
-    .try_for_each(|(path, item)| -> Result<()> {
-      if let Some(item) = item.as_item_mut() {
-        item.iter_mut().try_for_each(|(method, op)| -> Result<()> {
-          if let Some(opid) = op.operation_id.as_ref() {
-            opids.insert_opid_with_path_method(opid, path, method)?;
-          } else {
-            let opid = opids.insert_synthetic_opid_for_path_method(path, method)?;
-            op.operation_id = Some(opid);
-          }
-          Ok(())
-        })?;
-      }
-      Ok(())
-    })
-}
-
-fn main() -> Result<()> {
-  let src = "./generator/swagger/v4.json";
-  let dst = "./generator/swagger/generated-opids.json";
-  let in_file = std::fs::File::open(src)?;
-  let out_file = std::fs::File::create_new(dst)?;
-  let mut spec = serde_json::from_reader(in_file)?;
-  gen_operation_ids(&mut spec)?;
-  serde_json::to_writer_pretty(out_file, &spec)?;
-  Ok(())
+fn test_exit_code_on_warnings() {
+  use clap::Parser;
+
+  let dir = std::env::temp_dir().join(format!("verge-warnings-{}", std::process::id()));
+  let _ = std::fs::remove_dir_all(&dir);
+  std::fs::create_dir_all(&dir).unwrap();
+  let input = dir.join("spec.json");
+  let output = dir.join("out.json");
+  // the path item without operations is warned about
+  std::fs::write(
+    &input,
+    r#"{
+      "openapi": "3.0.1",
+      "info": { "title": "test", "version": "1" },
+      "paths": { "/users": { "get": { "responses": {} } }, "/old": {} }
+    }"#,
+  )
+  .unwrap();
+
+  let args = [
+    "verge",
+    "--input",
+    input.to_str().unwrap(),
+    "--output",
+    output.to_str().unwrap(),
+    "--exit-code-on-warnings",
+  ];
+  let matches = Cli::command().get_matches_from(args);
+  let cli = Cli::from_arg_matches(&matches).unwrap();
+  let printed = run(&cli, &matches).unwrap();
+  assert_eq!(printed, 1);
+  // the output is written all the same
+  assert!(output.exists());
+  let err = fail_on_warnings(&cli, printed).unwrap_err();
+  assert!(err.to_string().contains("--exit-code-on-warnings"), "{err}");
+  assert!(fail_on_warnings(&cli, 0).is_ok());
+
+  let cli = Cli::parse_from(&args[..args.len() - 1]);
+  assert!(fail_on_warnings(&cli, printed).is_ok());
+
+  std::fs::remove_dir_all(&dir).unwrap();
 }
@@ -0,0 +1,674 @@
+//! Mapping files list the operation IDs assigned to endpoints outside of
+//! the spec, so they can be curated by hand.
+
+use anyhow::{anyhow, Result};
+use openapiv3::{OpenAPI, Operation};
+use serde::{Deserialize, Serialize};
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::io::Write;
+use std::path::Path;
+
+use crate::cli::ValidateMappingArgs;
+use crate::generate::{default_strategy, gen_operation_ids_with_strategy};
+use crate::input::read_spec;
+use crate::opid::OperationIds;
+use crate::output;
+use crate::settings::Settings;
+use crate::strategy::IdStrategy;
+
+/// The operation ID of one endpoint
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MappingEntry {
+  pub operation_id: String,
+  /// Lowercase HTTP method
+  pub method: String,
+  /// The path as the spec spells it, parameters included
+  pub path: String,
+}
+
+/// What the entries of a written mapping file are sorted by
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum MappingKey {
+  /// The operation ID
+  Opid,
+  /// The path, then the method
+  #[default]
+  Path,
+}
+
+/// Read the mapping file at `path`. A file that does not exist yet is an
+/// empty mapping.
+pub fn read_mapping(path: &Path) -> Result<Vec<MappingEntry>> {
+  if !path.exists() {
+    return Ok(Vec::new());
+  }
+  let content = std::fs::read_to_string(path)?;
+  serde_json::from_str(&content)
+    .map_err(|err| anyhow!("invalid mapping file {}: {err}", path.display()))
+}
+
+/// Write the mapping file at `path`, replacing it if it exists
+pub fn write_mapping(path: &Path, mapping: &[MappingEntry]) -> Result<()> {
+  let content = serialize_mapping(mapping)?;
+  output::write_output(path, true, |writer| {
+    Ok(writer.write_all(content.as_bytes())?)
+  })
+}
+
+/// The content of a mapping file, pretty printed JSON ending in a newline
+fn serialize_mapping(mapping: &[MappingEntry]) -> Result<String> {
+  let mut content = serde_json::to_string_pretty(mapping)?;
+  content.push('\n');
+  Ok(content)
+}
+
+/// Read a list of paths, one per line, like the changed paths of a commit.
+/// Blank lines are skipped.
+pub fn read_paths(path: &Path) -> Result<BTreeSet<String>> {
+  Ok(
+    std::fs::read_to_string(path)?
+      .lines()
+      .map(str::trim)
+      .filter(|line| !line.is_empty())
+      .map(String::from)
+      .collect(),
+  )
+}
+
+/// Drop the entries for the spec paths listed in `paths` from the mapping,
+/// so [`append_to_mapping`] generates their operation IDs afresh while all
+/// other endpoints keep theirs. Entries for listed paths the spec does not
+/// have are kept, like any entry for an endpoint that is gone.
+pub fn forget_paths(
+  spec: &OpenAPI,
+  mapping: Vec<MappingEntry>,
+  paths: &BTreeSet<String>,
+) -> Vec<MappingEntry> {
+  mapping
+    .into_iter()
+    .filter(|entry| {
+      !(paths.contains(&entry.path) && spec.paths.paths.contains_key(&entry.path))
+    })
+    .collect()
+}
+
+/// Check that `mapping` matches the spec: every operation lacking an
+/// operation ID has exactly one entry, entries for operations with an
+/// explicit operation ID agree with it, no operation ID is mapped twice,
+/// and no entry is left over for an operation the spec does not have.
+/// Returns a description of every mismatch.
+pub fn check_mapping(spec: &OpenAPI, mapping: &[MappingEntry]) -> Result<Vec<String>> {
+  let mut explicit = OperationIds::default();
+  let mut unnamed = BTreeSet::new();
+  for (path, item) in spec.paths.paths.iter() {
+    let Some(item) = item.as_item() else {
+      continue;
+    };
+    for (method, op) in item.iter() {
+      match &op.operation_id {
+        Some(opid) => explicit.insert_opid_with_path_method(opid, path, method)?,
+        None => {
+          unnamed.insert((path.clone(), method.to_string()));
+        }
+      }
+    }
+  }
+
+  let mut problems = Vec::new();
+  let mut mapped = BTreeMap::new();
+  let mut opids = BTreeMap::new();
+  for entry in mapping {
+    let endpoint = format!("{} {}", entry.method.to_uppercase(), entry.path);
+    let key = (entry.path.clone(), entry.method.clone());
+    if mapped.insert(key.clone(), &entry.operation_id).is_some() {
+      problems.push(format!("{endpoint} is mapped more than once"));
+    }
+    if let Some(other) = opids.insert(&entry.operation_id, endpoint.clone()) {
+      problems.push(format!(
+        "operation id {:?} is mapped to both {other} and {endpoint}",
+        entry.operation_id
+      ));
+    }
+    match explicit.opid_for_path_method(&entry.path, &entry.method) {
+      Some(opid) if opid != entry.operation_id => problems.push(format!(
+        "{endpoint} is mapped to {:?}, but the spec names it {opid:?}",
+        entry.operation_id
+      )),
+      Some(_) => {}
+      None if !unnamed.contains(&key) => problems.push(format!(
+        "mapping entry {:?} for {endpoint} has no operation in the spec",
+        entry.operation_id
+      )),
+      None => {}
+    }
+  }
+  unnamed
+    .iter()
+    .filter(|key| !mapped.contains_key(*key))
+    .for_each(|(path, method)| {
+      problems.push(format!(
+        "{} {path} has no operation id and no mapping entry",
+        method.to_uppercase()
+      ));
+    });
+  Ok(problems)
+}
+
+/// Endpoints of `mapping` that got a different operation ID in `opids`,
+/// described like `GET /users: users_get -> users1_get`. Entries for
+/// endpoints that are gone are no drift, since no client method changes
+/// its name because of them.
+pub fn drift(opids: &OperationIds, mapping: &[MappingEntry]) -> Vec<String> {
+  mapping
+    .iter()
+    .filter_map(|entry| {
+      let opid = opids.opid_for_path_method(&entry.path, &entry.method)?;
+      (opid != entry.operation_id).then(|| {
+        format!(
+          "{} {}: {} -> {opid}",
+          entry.method.to_uppercase(),
+          entry.path,
+          entry.operation_id
+        )
+      })
+    })
+    .collect()
+}
+
+/// Fail unless every endpoint of the mapping file at `path` kept its
+/// operation ID in `opids`, for gating changes to the spec on a stable
+/// client
+pub fn check_stability(opids: &OperationIds, path: &Path) -> Result<()> {
+  if !path.exists() {
+    return Err(anyhow!("mapping file {} does not exist", path.display()));
+  }
+  let drift = drift(opids, &read_mapping(path)?);
+  if drift.is_empty() {
+    Ok(())
+  } else {
+    Err(anyhow!(
+      "{} endpoints of {} would change their operation id:\n  {}",
+      drift.len(),
+      path.display(),
+      drift.join("\n  ")
+    ))
+  }
+}
+
+/// Run the `validate-mapping` command
+pub fn run_validate(args: &ValidateMappingArgs) -> Result<()> {
+  let spec = read_spec(&args.spec, args.json5)?;
+  if !args.mapping.exists() {
+    return Err(anyhow!(
+      "mapping file {} does not exist",
+      args.mapping.display()
+    ));
+  }
+  let problems = check_mapping(&spec, &read_mapping(&args.mapping)?)?;
+  if problems.is_empty() {
+    Ok(())
+  } else {
+    Err(anyhow!(
+      "{} does not match {}:\n  {}",
+      args.mapping.display(),
+      args.spec.display(),
+      problems.join("\n  ")
+    ))
+  }
+}
+
+/// The mapping of all synthetic operation IDs, sorted by `key` so that the
+/// same operation IDs always make for the same mapping file
+pub fn mapping_of(opids: &OperationIds, key: MappingKey) -> Vec<MappingEntry> {
+  let mut mapping: Vec<MappingEntry> = opids
+    .synthetic_endpoints()
+    .map(|(key, opid)| MappingEntry {
+      operation_id: opid.to_string(),
+      method: key.method().to_string(),
+      path: key.path_with_params(),
+    })
+    .collect();
+  match key {
+    MappingKey::Opid => mapping.sort_by(|a, b| a.operation_id.cmp(&b.operation_id)),
+    MappingKey::Path => {
+      mapping.sort_by(|a, b| (&a.path, &a.method).cmp(&(&b.path, &b.method)))
+    }
+  }
+  mapping
+}
+
+/// A row of the CSV export, see [`csv_of`]
+#[derive(Serialize)]
+struct CsvRow<'a> {
+  operation_id: &'a str,
+  method: &'a str,
+  path: String,
+  origin: String,
+}
+
+/// All operation IDs as CSV with an `operation_id,method,path,origin`
+/// header, sorted by path and method, for spreadsheets
+pub fn csv_of(opids: &OperationIds) -> Result<String> {
+  let mut rows = opids
+    .iter()
+    .map(|(opid, key)| CsvRow {
+      operation_id: opid,
+      method: key.method(),
+      path: key.path_with_params(),
+      origin: opids
+        .origin_of(opid)
+        .map(|origin| origin.to_string())
+        .unwrap_or_default(),
+    })
+    .collect::<Vec<_>>();
+  rows.sort_by(|a, b| (&a.path, a.method).cmp(&(&b.path, b.method)));
+
+  let mut writer = csv::Writer::from_writer(Vec::new());
+  for row in rows {
+    writer.serialize(row)?;
+  }
+  let content = writer
+    .into_inner()
+    .map_err(|err| anyhow!("writing the CSV failed: {err}"))?;
+  Ok(String::from_utf8(content)?)
+}
+
+/// Write [`csv_of`] to `path`, replacing the file
+pub fn write_csv(path: &Path, opids: &OperationIds) -> Result<()> {
+  let content = csv_of(opids)?;
+  output::write_output(path, true, |writer| {
+    Ok(writer.write_all(content.as_bytes())?)
+  })
+}
+
+/// Names the endpoints of a mapping the way the mapping says, and leaves
+/// the others to `fallback`
+struct MappingStrategy {
+  entries: BTreeMap<(String, String), String>,
+  fallback: Box<dyn IdStrategy>,
+}
+
+impl IdStrategy for MappingStrategy {
+  fn id(
+    &self,
+    path: &str,
+    method: &str,
+    op: &Operation,
+    ids: &OperationIds,
+  ) -> Option<String> {
+    match self.entries.get(&(path.to_string(), method.to_string())) {
+      Some(opid) => Some(opid.clone()),
+      None => self.fallback.id(path, method, op, ids),
+    }
+  }
+
+  fn discriminator(&self, path: &str, method: &str, op: &Operation) -> Option<String> {
+    self.fallback.discriminator(path, method, op)
+  }
+}
+
+/// Assign operation IDs to the operations of the spec lacking one, taking
+/// them from `mapping` where it has an entry for the endpoint, and
+/// generating them only for the endpoints it is missing, the way
+/// [`crate::generate::gen_operation_ids`] would.
+///
+/// Returns the operation IDs along with the extended mapping: the entries
+/// of `mapping` come first and in their original order, followed by the
+/// generated ones in canonical path and method order. Entries for endpoints
+/// the spec no longer has are kept, and their operation IDs are not handed
+/// out to other endpoints.
+pub fn append_to_mapping(
+  spec: &mut OpenAPI,
+  settings: &Settings,
+  mut mapping: Vec<MappingEntry>,
+) -> Result<(OperationIds, Vec<MappingEntry>)> {
+  let endpoints: BTreeSet<(String, String)> = spec
+    .paths
+    .paths
+    .iter()
+    .filter_map(|(path, item)| item.as_item().map(|item| (path, item)))
+    .flat_map(|(path, item)| {
+      item
+        .iter()
+        .map(move |(method, _)| (path.clone(), method.to_string()))
+    })
+    .collect();
+
+  let mut settings = settings.clone();
+  settings.reserved_operation_ids.extend(
+    mapping
+      .iter()
+      .filter(|entry| !endpoints.contains(&(entry.path.clone(), entry.method.clone())))
+      .map(|entry| entry.operation_id.clone()),
+  );
+  let strategy = MappingStrategy {
+    entries: mapping
+      .iter()
+      .map(|entry| {
+        (
+          (entry.path.clone(), entry.method.clone()),
+          entry.operation_id.clone(),
+        )
+      })
+      .collect(),
+    fallback: default_strategy(spec, &settings)?,
+  };
+  let opids = gen_operation_ids_with_strategy(spec, &settings, &strategy)?;
+
+  let new_entries: Vec<MappingEntry> = mapping_of(&opids, MappingKey::Path)
+    .into_iter()
+    .filter(|entry| {
+      !strategy
+        .entries
+        .contains_key(&(entry.path.clone(), entry.method.clone()))
+    })
+    .collect();
+  mapping.extend(new_entries);
+  Ok((opids, mapping))
+}
+
+#[test]
+fn test_append_to_mapping() {
+  let mut spec = crate::spec::mk_spec(
+    r#"{
+      "/users": {
+        "get": { "responses": {} },
+        "post": { "responses": {} }
+      },
+      "/users/{id}": {
+        "get": { "responses": {} },
+        "delete": { "operationId": "remove_user", "responses": {} }
+      },
+      "/groups": { "get": { "responses": {} } }
+    }"#,
+  );
+  let entry = |operation_id: &str, method: &str, path: &str| MappingEntry {
+    operation_id: operation_id.to_string(),
+    method: method.to_string(),
+    path: path.to_string(),
+  };
+  // deliberately not in canonical order, and with an endpoint that is gone
+  let mapping = vec![
+    entry("get_user", "get", "/users/{id}"),
+    entry("groups_get", "delete", "/groups"),
+    entry("list_users", "get", "/users"),
+  ];
+
+  let (opids, mapping) =
+    append_to_mapping(&mut spec, &Settings::default(), mapping).unwrap();
+  assert_eq!(
+    mapping,
+    [
+      entry("get_user", "get", "/users/{id}"),
+      entry("groups_get", "delete", "/groups"),
+      entry("list_users", "get", "/users"),
+      // `groups_get` stays with the removed endpoint
+      entry("groups1_get", "get", "/groups"),
+      entry("users_post", "post", "/users"),
+    ]
+  );
+  assert_eq!(
+    opids.opid_for_path_method("/users/{id}", "get"),
+    Some("get_user")
+  );
+  assert_eq!(
+    opids.opid_for_path_method("/users/{id}", "delete"),
+    Some("remove_user")
+  );
+  let item = spec.paths.paths["/users"].as_item().unwrap();
+  assert_eq!(
+    item.get.as_ref().unwrap().operation_id.as_deref(),
+    Some("list_users")
+  );
+}
+
+#[test]
+fn test_append_to_mapping_operation_names() {
+  let mut spec = crate::spec::mk_spec(
+    r#"{
+      "/users": { "get": { "responses": {} }, "post": { "responses": {} } },
+      "/groups": { "get": { "responses": {} } }
+    }"#,
+  );
+  spec.info.extensions.insert(
+    crate::spec::OPERATION_NAMES_EXTENSION.to_string(),
+    serde_json::json!({ "GET /users": "list_users", "GET /groups": "list_groups" }),
+  );
+  let settings = Settings {
+    info_operation_names: true,
+    ..Settings::default()
+  };
+  let mapping = vec![MappingEntry {
+    operation_id: "get_groups".to_string(),
+    method: "get".to_string(),
+    path: "/groups".to_string(),
+  }];
+
+  let (opids, mapping) = append_to_mapping(&mut spec, &settings, mapping).unwrap();
+  // the mapping wins over the names of the spec, which win over synthetic IDs
+  assert_eq!(
+    opids.opid_for_path_method("/groups", "get"),
+    Some("get_groups")
+  );
+  assert_eq!(
+    opids.opid_for_path_method("/users", "get"),
+    Some("list_users")
+  );
+  assert_eq!(
+    opids.opid_for_path_method("/users", "post"),
+    Some("users_post")
+  );
+  assert_eq!(mapping.len(), 3);
+}
+
+#[test]
+fn test_append_to_mapping_request_content_type() {
+  let mut spec = crate::spec::mk_spec(
+    r#"{
+      "/files": {
+        "post": { "requestBody": { "content": { "multipart/form-data": {} } }, "responses": {} }
+      },
+      "/files/": {
+        "post": { "requestBody": { "content": { "application/json": {} } }, "responses": {} }
+      }
+    }"#,
+  );
+  let settings = Settings {
+    request_content_type: true,
+    ..Settings::default()
+  };
+
+  let (_, mapping) = append_to_mapping(&mut spec, &settings, Vec::new()).unwrap();
+  let opids: Vec<_> = mapping
+    .iter()
+    .map(|entry| &entry.operation_id[..])
+    .collect();
+  assert_eq!(opids, ["files_post_multipart", "files_post_json"]);
+}
+
+#[test]
+fn test_mapping_of_is_stable() {
+  let forward = r#"{
+    "/users": { "get": { "responses": {} }, "post": { "responses": {} } },
+    "/a_b": { "get": { "responses": {} } },
+    "/a/{id}": { "get": { "responses": {} } }
+  }"#;
+  let backward = r#"{
+    "/a/{id}": { "get": { "responses": {} } },
+    "/a_b": { "get": { "responses": {} } },
+    "/users": { "post": { "responses": {} }, "get": { "responses": {} } }
+  }"#;
+  let content = |paths: &str, key: MappingKey| {
+    let mut spec = crate::spec::mk_spec(paths);
+    let opids =
+      crate::generate::gen_operation_ids(&mut spec, &Settings::default()).unwrap();
+    serialize_mapping(&mapping_of(&opids, key)).unwrap()
+  };
+
+  for key in [MappingKey::Opid, MappingKey::Path] {
+    assert_eq!(content(forward, key), content(forward, key));
+    assert_eq!(content(forward, key), content(backward, key));
+  }
+  let order = |key: MappingKey| {
+    let mut spec = crate::spec::mk_spec(forward);
+    let opids =
+      crate::generate::gen_operation_ids(&mut spec, &Settings::default()).unwrap();
+    mapping_of(&opids, key)
+      .into_iter()
+      .map(|entry| entry.operation_id)
+      .collect::<Vec<_>>()
+  };
+  assert_eq!(
+    order(MappingKey::Path),
+    ["a_get_by_id", "a_b_get", "users_get", "users_post"]
+  );
+  assert_eq!(
+    order(MappingKey::Opid),
+    ["a_b_get", "a_get_by_id", "users_get", "users_post"]
+  );
+}
+
+#[test]
+fn test_drift() {
+  let entry = |operation_id: &str, path: &str| MappingEntry {
+    operation_id: operation_id.to_string(),
+    method: "get".to_string(),
+    path: path.to_string(),
+  };
+  let mapping = [
+    entry("users_get", "/users"),
+    entry("users_get_by_id", "/users/{id}"),
+    entry("legacy_get", "/legacy"),
+  ];
+  let opids = |paths: &str| {
+    let mut spec = crate::spec::mk_spec(paths);
+    crate::generate::gen_operation_ids(&mut spec, &Settings::default()).unwrap()
+  };
+
+  let stable = opids(
+    r#"{
+      "/users": { "get": { "responses": {} } },
+      "/users/{id}": { "get": { "responses": {} } }
+    }"#,
+  );
+  assert!(drift(&stable, &mapping).is_empty());
+
+  // the new explicit operation ID pushes `GET /users` aside
+  let drifted = opids(
+    r#"{
+      "/users": { "get": { "responses": {} } },
+      "/users/{id}": { "get": { "responses": {} } },
+      "/accounts": { "get": { "operationId": "users_get", "responses": {} } }
+    }"#,
+  );
+  assert_eq!(
+    drift(&drifted, &mapping),
+    ["GET /users: users_get -> users1_get"]
+  );
+}
+
+#[test]
+fn test_forget_paths() {
+  let mut spec = crate::spec::mk_spec(
+    r#"{
+      "/users": { "get": { "responses": {} } },
+      "/users/{id}": { "get": { "responses": {} } }
+    }"#,
+  );
+  let entry = |operation_id: &str, path: &str| MappingEntry {
+    operation_id: operation_id.to_string(),
+    method: "get".to_string(),
+    path: path.to_string(),
+  };
+  let mapping = vec![
+    entry("list_users", "/users"),
+    entry("fetch_user", "/users/{id}"),
+    entry("legacy_get", "/legacy"),
+  ];
+  let changed = ["/users/{id}", "/legacy"].map(String::from).into();
+
+  let mapping = forget_paths(&spec, mapping, &changed);
+  let (opids, mapping) =
+    append_to_mapping(&mut spec, &Settings::default(), mapping).unwrap();
+  assert_eq!(
+    mapping,
+    [
+      entry("list_users", "/users"),
+      entry("legacy_get", "/legacy"),
+      entry("users_get_by_id", "/users/{id}"),
+    ]
+  );
+  assert_eq!(
+    opids.opid_for_path_method("/users", "get"),
+    Some("list_users")
+  );
+}
+
+#[test]
+fn test_check_mapping() {
+  let spec = crate::spec::mk_spec(
+    r#"{
+      "/users": {
+        "get": { "operationId": "list_users", "responses": {} },
+        "post": { "responses": {} }
+      },
+      "/users/{id}": {
+        "get": { "responses": {} },
+        "delete": { "responses": {} }
+      }
+    }"#,
+  );
+  let entry = |operation_id: &str, method: &str, path: &str| MappingEntry {
+    operation_id: operation_id.to_string(),
+    method: method.to_string(),
+    path: path.to_string(),
+  };
+
+  let mapping = [
+    entry("list_users", "get", "/users"),
+    entry("create_user", "post", "/users"),
+    entry("get_user", "get", "/users/{id}"),
+    entry("remove_user", "delete", "/users/{id}"),
+  ];
+  assert!(check_mapping(&spec, &mapping).unwrap().is_empty());
+
+  let mapping = [
+    entry("all_users", "get", "/users"),
+    entry("create_user", "post", "/users"),
+    entry("create_user", "get", "/users/{id}"),
+    entry("legacy", "get", "/legacy"),
+  ];
+  assert_eq!(
+    check_mapping(&spec, &mapping).unwrap(),
+    [
+      "GET /users is mapped to \"all_users\", but the spec names it \"list_users\"",
+      "operation id \"create_user\" is mapped to both POST /users and GET /users/{id}",
+      "mapping entry \"legacy\" for GET /legacy has no operation in the spec",
+      "DELETE /users/{id} has no operation id and no mapping entry",
+    ]
+  );
+}
+
+#[test]
+fn test_csv_of() {
+  let mut opids = OperationIds::default();
+  opids
+    .insert_opid_with_path_method("search, all", "/users/{id}", "get")
+    .unwrap();
+  opids
+    .insert_synthetic_opid_for_path_method("/groups", "post")
+    .unwrap();
+  opids
+    .insert_synthetic_opid_for_path_method("/users", "get")
+    .unwrap();
+
+  assert_eq!(
+    csv_of(&opids).unwrap(),
+    "operation_id,method,path,origin\n\
+     groups_post,post,/groups,synthetic\n\
+     users_get,get,/users,synthetic\n\
+     \"search, all\",get,/users/{id},explicit\n"
+  );
+}
@@ -0,0 +1,313 @@
+use anyhow::{anyhow, Result};
+use openapiv3::{Components, OpenAPI, PathItem, ReferenceOr};
+use serde_json::Value;
+
+use std::collections::BTreeMap;
+
+use crate::spec::{operation_slot, without_operations};
+
+/// Merge several specs into one, so that a single client with a single
+/// operation ID namespace can be generated for all of them. Every spec is
+/// given along with a name used in error messages, usually its file name.
+///
+/// Info, servers and security are taken from the first spec. Paths, tags
+/// and components are combined; defining the same path and method, the
+/// same explicit operation ID, or two different components of the same
+/// name in more than one place is an error. Specs sharing a path share its
+/// path level parameters and servers, and so must not define the same
+/// parameter differently.
+pub fn merge_specs(specs: Vec<(String, OpenAPI)>) -> Result<OpenAPI> {
+  let mut specs = specs.into_iter();
+  let (first_name, first) = specs.next().ok_or_else(|| anyhow!("no specs to merge"))?;
+
+  let mut merged = first.clone();
+  merged.paths.paths.clear();
+  let mut merger = Merger::default();
+  merger.merge_paths(&mut merged, &first, &first_name)?;
+
+  for (name, spec) in specs {
+    merger.merge_paths(&mut merged, &spec, &name)?;
+    for tag in spec.tags {
+      if !merged.tags.iter().any(|t| t.name == tag.name) {
+        merged.tags.push(tag);
+      }
+    }
+    merge_components(&mut merged.components, spec.components, &name)?;
+  }
+  Ok(merged)
+}
+
+/// Bookkeeping of where endpoints and explicit operation IDs came from
+#[derive(Default)]
+struct Merger {
+  endpoints: BTreeMap<(String, String), String>,
+  explicit_opids: BTreeMap<String, (String, String)>,
+}
+
+impl Merger {
+  fn merge_paths(
+    &mut self,
+    merged: &mut OpenAPI,
+    spec: &OpenAPI,
+    name: &str,
+  ) -> Result<()> {
+    for (path, item) in spec.paths.paths.iter() {
+      let Some(item) = item.as_item() else {
+        if merged.paths.paths.contains_key(path) {
+          return Err(anyhow!("path {path} from {name} is already defined"));
+        }
+        merged.paths.paths.insert(path.clone(), item.clone());
+        continue;
+      };
+
+      let shared = merged.paths.paths.contains_key(path);
+      let merged_item = merged
+        .paths
+        .paths
+        .entry(path.clone())
+        .or_insert_with(|| ReferenceOr::Item(without_operations(item)))
+        .as_item_mut()
+        .ok_or_else(|| {
+          anyhow!("path {path} from {name} is already defined as a reference")
+        })?;
+      if shared {
+        merge_path_level(merged_item, item, path, name)?;
+      }
+
+      for (method, op) in item.iter() {
+        let endpoint = format!("{} {path}", method.to_uppercase());
+        if let Some(other) = self
+          .endpoints
+          .insert((path.clone(), method.to_string()), name.to_string())
+        {
+          return Err(anyhow!("{endpoint} is defined in both {other} and {name}"));
+        }
+
+        if let Some(opid) = &op.operation_id {
+          if let Some((other, other_endpoint)) = self.explicit_opids.get(opid) {
+            return Err(anyhow!(
+              "operation id {opid:?} is used by both {other_endpoint} in {other} \
+               and {endpoint} in {name}"
+            ));
+          }
+          self
+            .explicit_opids
+            .insert(opid.clone(), (name.to_string(), endpoint));
+        }
+
+        if let Some(slot) = operation_slot(merged_item, method) {
+          *slot = Some(op.clone());
+        }
+      }
+    }
+    Ok(())
+  }
+}
+
+/// What tells path level parameters apart, as JSON: the location and name
+/// of a parameter, or the target of a reference
+fn param_identity(param: &Value) -> (String, String) {
+  let field = |key: &str| param[key].as_str().unwrap_or_default().to_string();
+  match param.get("$ref") {
+    Some(_) => ("$ref".to_string(), field("$ref")),
+    None => (field("in"), field("name")),
+  }
+}
+
+/// Add the path level parameters and servers of `item` from the spec
+/// `name` to those of `merged_item`, which the earlier specs defined for
+/// `path`. A parameter defined differently there is an error.
+fn merge_path_level(
+  merged_item: &mut PathItem,
+  item: &PathItem,
+  path: &str,
+  name: &str,
+) -> Result<()> {
+  for param in &item.parameters {
+    let value = serde_json::to_value(param)?;
+    let identity = param_identity(&value);
+    let mut existing = None;
+    for merged_param in &merged_item.parameters {
+      let merged_value = serde_json::to_value(merged_param)?;
+      if param_identity(&merged_value) == identity {
+        existing = Some(merged_value);
+        break;
+      }
+    }
+    match existing {
+      Some(existing) if existing != value => {
+        let (location, param_name) = identity;
+        return Err(anyhow!(
+          "path level parameter {param_name:?} in {location} of {path} from {name} \
+           conflicts with an earlier definition"
+        ));
+      }
+      Some(_) => {}
+      None => merged_item.parameters.push(param.clone()),
+    }
+  }
+  for server in &item.servers {
+    if !merged_item.servers.contains(server) {
+      merged_item.servers.push(server.clone());
+    }
+  }
+  Ok(())
+}
+
+/// Merge the components of `from` into `into`, section by section. The
+/// sections are handled as plain JSON, so that every kind of component is
+/// covered the same way.
+fn merge_components(
+  into: &mut Option<Components>,
+  from: Option<Components>,
+  name: &str,
+) -> Result<()> {
+  let Some(from) = from else {
+    return Ok(());
+  };
+  let Some(components) = into.as_mut() else {
+    *into = Some(from);
+    return Ok(());
+  };
+
+  let mut merged = serde_json::to_value(&*components)?;
+  if let (Value::Object(sections), Value::Object(from_sections)) =
+    (&mut merged, serde_json::to_value(from)?)
+  {
+    for (section, from_entries) in from_sections {
+      let Some(Value::Object(entries)) = sections.get_mut(&section) else {
+        sections.entry(section).or_insert(from_entries);
+        continue;
+      };
+      let Value::Object(from_entries) = from_entries else {
+        continue;
+      };
+      for (key, entry) in from_entries {
+        match entries.get(&key) {
+          Some(existing) if *existing != entry => {
+            return Err(anyhow!(
+              "component {section}/{key} from {name} conflicts with an earlier definition"
+            ));
+          }
+          Some(_) => {}
+          None => {
+            entries.insert(key, entry);
+          }
+        }
+      }
+    }
+  }
+  *components = serde_json::from_value(merged)?;
+  Ok(())
+}
+
+#[cfg(test)]
+use crate::spec::mk_spec;
+
+#[test]
+fn test_merge_specs() {
+  let mut a = mk_spec(
+    r#"{
+      "/users": { "get": { "operationId": "list_users", "responses": {} } }
+    }"#,
+  );
+  let mut b = mk_spec(
+    r#"{
+      "/users": { "post": { "responses": {} } },
+      "/groups": { "get": { "responses": {} } }
+    }"#,
+  );
+  a.components = serde_json::from_str(r#"{ "schemas": { "User": {} } }"#).unwrap();
+  b.components = serde_json::from_str(r#"{ "schemas": { "Group": {} } }"#).unwrap();
+
+  let mut merged = merge_specs(vec![("a.json".into(), a), ("b.json".into(), b)]).unwrap();
+  let schemas = &merged.components.as_ref().unwrap().schemas;
+  assert!(schemas.contains_key("User") && schemas.contains_key("Group"));
+
+  crate::generate::gen_operation_ids(&mut merged, &Default::default()).unwrap();
+  let users = merged.paths.paths["/users"].as_item().unwrap();
+  assert_eq!(
+    users.get.as_ref().unwrap().operation_id.as_deref(),
+    Some("list_users")
+  );
+  assert_eq!(
+    users.post.as_ref().unwrap().operation_id.as_deref(),
+    Some("users_post")
+  );
+}
+
+#[test]
+fn test_merge_specs_conflicts() {
+  let a =
+    mk_spec(r#"{ "/users": { "get": { "operationId": "users", "responses": {} } } }"#);
+
+  // the same endpoint in two specs
+  let b = mk_spec(r#"{ "/users": { "get": { "responses": {} } } }"#);
+  let err = merge_specs(vec![("a.json".into(), a.clone()), ("b.json".into(), b)])
+    .unwrap_err()
+    .to_string();
+  assert_eq!(err, "GET /users is defined in both a.json and b.json");
+
+  // the same explicit operation id in two specs
+  let b =
+    mk_spec(r#"{ "/groups": { "get": { "operationId": "users", "responses": {} } } }"#);
+  let err = merge_specs(vec![("a.json".into(), a.clone()), ("b.json".into(), b)])
+    .unwrap_err()
+    .to_string();
+  assert_eq!(
+    err,
+    "operation id \"users\" is used by both GET /users in a.json and GET /groups in b.json"
+  );
+
+  // a component of the same name that is defined differently
+  let mut a = a;
+  let mut b = mk_spec(r#"{}"#);
+  a.components = serde_json::from_str(r#"{ "schemas": { "User": {} } }"#).unwrap();
+  b.components =
+    serde_json::from_str(r#"{ "schemas": { "User": { "type": "string" } } }"#).unwrap();
+  let err = merge_specs(vec![("a.json".into(), a), ("b.json".into(), b)])
+    .unwrap_err()
+    .to_string();
+  assert_eq!(
+    err,
+    "component schemas/User from b.json conflicts with an earlier definition"
+  );
+}
+
+#[test]
+fn test_merge_path_level_parameters() {
+  let param = |name: &str, kind: &str| {
+    format!(
+      r#"{{ "name": "{name}", "in": "path", "required": true,
+        "schema": {{ "type": "{kind}" }} }}"#
+    )
+  };
+  let spec = |method: &str, params: &str| {
+    mk_spec(&format!(
+      r#"{{ "/users/{{id}}": {{
+        "parameters": [{params}],
+        "{method}": {{ "responses": {{}} }}
+      }} }}"#
+    ))
+  };
+  let a = spec("get", &param("id", "string"));
+  let b = spec(
+    "delete",
+    &format!("{}, {}", param("id", "string"), param("tenant", "string")),
+  );
+  let merged =
+    merge_specs(vec![("a.json".into(), a.clone()), ("b.json".into(), b)]).unwrap();
+  let item = merged.paths.paths["/users/{id}"].as_item().unwrap();
+  assert_eq!(item.parameters.len(), 2);
+  assert!(item.get.is_some() && item.delete.is_some());
+
+  let b = spec("delete", &param("id", "integer"));
+  let err = merge_specs(vec![("a.json".into(), a), ("b.json".into(), b)])
+    .unwrap_err()
+    .to_string();
+  assert_eq!(
+    err,
+    "path level parameter \"id\" in path of /users/{id} from b.json conflicts \
+     with an earlier definition"
+  );
+}
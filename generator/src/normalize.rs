@@ -0,0 +1,111 @@
+//! Canonicalizing the paths of a spec, independently of operation IDs.
+
+use anyhow::{anyhow, Result};
+use openapiv3::OpenAPI;
+
+use crate::cli::NormalizeArgs;
+use crate::input::read_spec;
+use crate::output;
+
+/// Canonical form of a path: runs of slashes collapse into one and a
+/// trailing slash is dropped, so `//users//{id}/` becomes `/users/{id}`.
+/// The root path stays `/`.
+pub fn normalize_path(path: &str) -> String {
+  let mut normalized = String::with_capacity(path.len());
+  for c in path.chars() {
+    if !(c == '/' && normalized.ends_with('/')) {
+      normalized.push(c);
+    }
+  }
+  if normalized.len() > 1 && normalized.ends_with('/') {
+    normalized.pop();
+  }
+  normalized
+}
+
+/// Normalize every path of the spec with [`normalize_path`] and sort the
+/// paths. Operations, operation IDs included, are left as they are. Fails
+/// if two paths end up the same, since their path items would have to be
+/// merged by hand.
+pub fn normalize_spec(spec: &mut OpenAPI) -> Result<()> {
+  let mut paths: Vec<_> = std::mem::take(&mut spec.paths.paths)
+    .into_iter()
+    .map(|(path, item)| (normalize_path(&path), path, item))
+    .collect();
+  paths.sort_by(|a, b| a.0.cmp(&b.0));
+
+  if let Some(pair) = paths.windows(2).find(|pair| pair[0].0 == pair[1].0) {
+    return Err(anyhow!(
+      "paths {} and {} both normalize to {}",
+      pair[0].1,
+      pair[1].1,
+      pair[0].0
+    ));
+  }
+  spec.paths.paths = paths
+    .into_iter()
+    .map(|(normalized, _, item)| (normalized, item))
+    .collect();
+  Ok(())
+}
+
+/// Run the `normalize` command
+pub fn run(args: &NormalizeArgs) -> Result<()> {
+  let mut spec = read_spec(&args.input, args.json5)?;
+  crate::validate::skip_null_path_items(&mut spec, false)?
+    .iter()
+    .for_each(|warning| eprintln!("{warning}"));
+  normalize_spec(&mut spec)?;
+  output::write_output(&args.output, args.overwrite, |writer| {
+    output::write_spec(writer, &spec, args.compact)
+  })
+}
+
+#[test]
+fn test_collapse_slashes() {
+  assert_eq!(normalize_path("/users//{id}"), "/users/{id}");
+  assert_eq!(normalize_path("//users///posts"), "/users/posts");
+}
+
+#[test]
+fn test_strip_trailing_slash() {
+  assert_eq!(normalize_path("/users/"), "/users");
+  assert_eq!(normalize_path("/users/{id}//"), "/users/{id}");
+  assert_eq!(normalize_path("/"), "/");
+  assert_eq!(normalize_path("//"), "/");
+}
+
+#[test]
+fn test_normalize_spec() {
+  let mut spec = crate::spec::mk_spec(
+    r#"{
+      "/users/": { "get": { "responses": {} } },
+      "/groups//{id}": { "get": { "operationId": "get_group", "responses": {} } },
+      "/admin": { "post": { "responses": {} } }
+    }"#,
+  );
+  normalize_spec(&mut spec).unwrap();
+  assert_eq!(
+    spec.paths.paths.keys().collect::<Vec<_>>(),
+    ["/admin", "/groups/{id}", "/users"]
+  );
+  let item = spec.paths.paths["/groups/{id}"].as_item().unwrap();
+  assert_eq!(
+    item.get.as_ref().unwrap().operation_id.as_deref(),
+    Some("get_group")
+  );
+  // no operation IDs are added
+  let item = spec.paths.paths["/users"].as_item().unwrap();
+  assert_eq!(item.get.as_ref().unwrap().operation_id, None);
+
+  let mut spec = crate::spec::mk_spec(
+    r#"{
+      "/users": { "get": { "responses": {} } },
+      "/users/": { "post": { "responses": {} } }
+    }"#,
+  );
+  assert_eq!(
+    normalize_spec(&mut spec).unwrap_err().to_string(),
+    "paths /users and /users/ both normalize to /users"
+  );
+}
@@ -0,0 +1,2934 @@
+use anyhow::{anyhow, Result};
+use openapiv3::Operation;
+use unicode_normalization::{is_nfc, UnicodeNormalization};
+
+use std::borrow::Cow;
+use std::collections::BTreeMap;
+use std::fmt;
+
+use crate::settings::{
+  CollisionStrategy, IntraSegment, Layout, MethodCase, MethodSuffix, NamingStyle,
+  Settings,
+};
+use crate::stats::Stats;
+use crate::warning::Warning;
+
+// Progenitor requires Operation ID to be specified for every operation
+// on every path. This is "holier than thou" compared to the OpenAPI spec
+// The code below creates operation IDs from paths, methods and optionally
+// path parameters
+// The bulk of this code is stolen from
+// https://github.com/upachler/progenitor/blob/1105-progenitor-requires-operationid-to-be-set/progenitor-impl/src/opid.rs
+//
+//
+/// newtype for encapsulating the combination of path and method,
+/// which can uniquely identify a HTTP endpoint. The struct is
+/// designed to be used as a key for map implementations
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct PathMethod {
+  path: String,
+  method: String,
+  params: Option<Vec<String>>,
+}
+
+impl PathMethod {
+  /// Create new PathMethod. This may fail if path or method
+  /// are empty, or if the path contains control characters. The method
+  /// is lowercased, so `GET` and `get` make the same key.
+  pub fn new(path: &str, method: &str, params: Option<Vec<&str>>) -> Result<Self> {
+    // disallow empty path/method
+    if path.is_empty() || method.is_empty() {
+      return Err(anyhow!("path and method may not be empty",));
+    }
+    // Control characters, NUL bytes in particular, are never part of a
+    // real path but of a corrupted spec
+    if path.contains(char::is_control) {
+      return Err(anyhow!(
+        "path {path:?} contains control characters, the spec is likely corrupt"
+      ));
+    }
+
+    // NOTE: In the future, we may consider checking for the proper URL path
+    // format in the the future according to the RFC:
+    // https://datatracker.ietf.org/doc/html/rfc3986#section-3.3
+
+    Ok(Self {
+      path: path.to_string(),
+      method: method.to_lowercase(),
+      params: params.map(|v| v.iter().map(|s| s.to_string()).collect()),
+    })
+  }
+
+  /// Placeholder key for operation IDs that are reserved without being
+  /// assigned to an endpoint. [`Self::new`] refuses empty paths and methods,
+  /// so it never equals the key of a real endpoint.
+  fn reserved() -> Self {
+    Self {
+      path: String::new(),
+      method: String::new(),
+      params: None,
+    }
+  }
+
+  fn is_reserved(&self) -> bool {
+    self.path.is_empty()
+  }
+
+  /// The HTTP method
+  pub fn method(&self) -> &str {
+    &self.method
+  }
+
+  /// Names of the path parameters, in the order they appear in the path
+  pub fn params(&self) -> &[String] {
+    self.params.as_deref().unwrap_or_default()
+  }
+
+  /// The path with the parameter names put back into their `{}`
+  /// placeholders, as it is written in the spec
+  pub fn path_with_params(&self) -> String {
+    let mut params = self.params.iter().flatten();
+    let mut path = String::with_capacity(self.path.len());
+    let mut rest = self.path.as_str();
+    while let Some(i) = rest.find("{}") {
+      path.push_str(&rest[..i]);
+      match params.next() {
+        Some(param) => path += &format!("{{{param}}}"),
+        None => path.push_str("{}"),
+      }
+      rest = &rest[i + 2..];
+    }
+    path.push_str(rest);
+    path
+  }
+}
+
+/// Formats as `METHOD /path/{param}`, the way endpoints show up in logs and
+/// error messages
+impl fmt::Display for PathMethod {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(
+      f,
+      "{} {}",
+      self.method.to_uppercase(),
+      self.path_with_params()
+    )
+  }
+}
+
+/// Details of an operation besides its path and method that synthetic
+/// operation IDs can be built from
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct OperationInfo {
+  /// The first tag of the operation
+  pub tag: Option<String>,
+  pub summary: Option<String>,
+  /// Whether other operations sit on the same path, for
+  /// [`MethodSuffix::Auto`]
+  pub shares_path: bool,
+  /// Word appended to synthetic operation IDs, see
+  /// [`crate::strategy::IdStrategy::discriminator`]
+  pub discriminator: Option<String>,
+  /// Path parameters catching all the rest of the path, marked in
+  /// synthetic operation IDs with [`Settings::mark_catch_all`]
+  pub catch_all: Vec<String>,
+}
+
+impl From<&Operation> for OperationInfo {
+  fn from(op: &Operation) -> Self {
+    Self {
+      tag: op.tags.first().cloned(),
+      summary: op.summary.clone(),
+      shares_path: false,
+      discriminator: None,
+      catch_all: Vec::new(),
+    }
+  }
+}
+
+/// Placeholders [`Settings::template`] may use
+pub const TEMPLATE_PLACEHOLDERS: [&str; 6] =
+  ["path", "method", "tag", "summary", "params", "attempt"];
+
+/// Check that a template only uses known placeholders
+pub fn check_template(template: &str) -> Result<()> {
+  let mut rest = template;
+  while let Some(start) = rest.find('{') {
+    let end = rest[start..]
+      .find('}')
+      .map(|end| start + end)
+      .ok_or_else(|| anyhow!("unterminated placeholder in template {template:?}"))?;
+    let placeholder = &rest[start + 1..end];
+    if !TEMPLATE_PLACEHOLDERS.contains(&placeholder) {
+      return Err(anyhow!(
+        "unknown placeholder {{{placeholder}}} in template {template:?}, expected one \
+         of {{{}}}",
+        TEMPLATE_PLACEHOLDERS.join("}, {")
+      ));
+    }
+    rest = &rest[end + 1..];
+  }
+  Ok(())
+}
+
+/// Decode `%XX` escapes and `+` in a path. Malformed escapes are kept as
+/// they are, and decoded bytes that are not UTF-8 become U+FFFD.
+fn percent_decode(path: &str) -> String {
+  let bytes = path.as_bytes();
+  let mut decoded = Vec::with_capacity(bytes.len());
+  let mut i = 0;
+  while i < bytes.len() {
+    let escape = bytes
+      .get(i + 1..i + 3)
+      .filter(|_| bytes[i] == b'%')
+      .filter(|hex| hex.iter().all(u8::is_ascii_hexdigit))
+      .and_then(|hex| u8::from_str_radix(std::str::from_utf8(hex).ok()?, 16).ok());
+    match (escape, bytes[i]) {
+      (Some(byte), _) => {
+        decoded.push(byte);
+        i += 3;
+        continue;
+      }
+      (None, b'+') => decoded.push(b' '),
+      (None, byte) => decoded.push(byte),
+    }
+    i += 1;
+  }
+  String::from_utf8_lossy(&decoded).into_owned()
+}
+
+/// Spell out every digit of an operation ID as a word of its own, so that
+/// no word touches a digit: `v2_items1_get` becomes
+/// `v_two_items_one_get`.
+fn spell_out_digits(opid: &str) -> String {
+  const DIGITS: [&str; 10] = [
+    "zero", "one", "two", "three", "four", "five", "six", "seven", "eight", "nine",
+  ];
+  let mut spelled = String::with_capacity(opid.len() * 2);
+  let mut after_digit = false;
+  for c in opid.chars() {
+    match c.to_digit(10) {
+      Some(digit) => {
+        if !spelled.is_empty() && !spelled.ends_with('_') {
+          spelled.push('_');
+        }
+        spelled.push_str(DIGITS[digit as usize]);
+        after_digit = true;
+      }
+      None => {
+        if after_digit && c != '_' {
+          spelled.push('_');
+        }
+        spelled.push(c);
+        after_digit = false;
+      }
+    }
+  }
+  spelled
+}
+
+/// Replace everything but letters and digits by `_`, and strip any `_` from
+/// both ends
+fn sanitize(s: &str) -> String {
+  s.replace(|c: char| !c.is_alphanumeric(), "_")
+    .trim_matches('_')
+    .to_string()
+}
+
+/// Split `s` into lowercase words, at everything but letters and digits and
+/// where the case changes, like `http_proxy` for `httpProxy`. A run of
+/// capitals is a word of its own, except for its last capital when a
+/// lowercase letter follows, like `http_proxy` for `HTTPProxy`. Runs made up
+/// of `acronyms` are split into them instead, like `xml_http_request` for
+/// `XMLHTTPRequest` with `XML` and `HTTP`.
+pub fn split_words(s: &str, acronyms: &[String]) -> Vec<String> {
+  let acronyms = acronyms
+    .iter()
+    .map(|acronym| acronym.to_uppercase().chars().collect::<Vec<_>>())
+    .filter(|acronym| !acronym.is_empty())
+    .collect::<Vec<_>>();
+  let mut words = Vec::new();
+  for chunk in s.split(|c: char| !c.is_alphanumeric()) {
+    let chars = chunk.chars().collect::<Vec<_>>();
+    let lowercase_run = |from: usize| {
+      from
+        + chars[from..]
+          .iter()
+          .take_while(|c| !c.is_uppercase())
+          .count()
+    };
+    let mut i = 0;
+    while i < chars.len() {
+      if !chars[i].is_uppercase() {
+        let end = lowercase_run(i);
+        words.push(chars[i..end].iter().collect::<String>());
+        i = end;
+        continue;
+      }
+      let run_end = i + chars[i..].iter().take_while(|c| c.is_uppercase()).count();
+      let mut pieces = Vec::new();
+      while let Some(acronym) = acronyms
+        .iter()
+        .filter(|acronym| chars[i..run_end].starts_with(acronym))
+        .max_by_key(|acronym| acronym.len())
+      {
+        pieces.push(acronym.iter().collect::<String>());
+        i += acronym.len();
+      }
+      if i == run_end && !pieces.is_empty() {
+        // a lowercase tail belongs to the last acronym, like `URLs`
+        let end = lowercase_run(i);
+        pieces.last_mut().unwrap().extend(chars[i..end].iter());
+        words.extend(pieces);
+        i = end;
+        continue;
+      }
+      words.extend(pieces);
+      let followed_by_lowercase = chars.get(run_end).is_some_and(|c| c.is_lowercase());
+      let word_start = if followed_by_lowercase && run_end - i > 1 {
+        words.push(chars[i..run_end - 1].iter().collect());
+        run_end - 1
+      } else {
+        i
+      };
+      let end = lowercase_run(run_end);
+      words.push(chars[word_start..end].iter().collect());
+      i = end;
+    }
+  }
+  words.iter().map(|word| word.to_lowercase()).collect()
+}
+
+/// The last `count` segments of `path` that are not a parameter, like
+/// `v1/users` for `/api/v1/users/{}` and a `count` of 2, along with how
+/// many there were
+fn last_segments(path: &str, count: usize) -> (String, usize) {
+  let mut segments = path
+    .rsplit('/')
+    .filter(|segment| !segment.is_empty() && *segment != "{}")
+    .take(count)
+    .collect::<Vec<_>>();
+  segments.reverse();
+  let taken = segments.len();
+  (segments.join("/"), taken)
+}
+
+/// `path` without the file extension of its last segment, like `/report`
+/// for `/report.pdf`. Segments that are a parameter, or nothing but an
+/// extension like `.well-known`, are kept.
+fn strip_extension(path: &str) -> &str {
+  let last = path.rsplit('/').next().unwrap_or_default();
+  match last.rfind('.') {
+    Some(dot) if dot > 0 && dot + 1 < last.len() && last != "{}" => {
+      &path[..path.len() - last.len() + dot]
+    }
+    _ => path,
+  }
+}
+
+/// Drop everything but letters and digits within the segments of `path`,
+/// like `/userprofiles/{id}` for `/user-profiles/{id}`, so each segment
+/// makes for a single word
+fn join_segments(path: &str) -> String {
+  path
+    .split('/')
+    .map(|segment| segment.replace(|c: char| !c.is_alphanumeric(), ""))
+    .collect::<Vec<_>>()
+    .join("/")
+}
+
+/// Join the `_` separated words of `opid` in camelCase, or PascalCase if
+/// `capitalize_first`
+fn camel_case(opid: &str, capitalize_first: bool) -> String {
+  let mut camel = String::with_capacity(opid.len());
+  for (i, word) in opid.split('_').filter(|word| !word.is_empty()).enumerate() {
+    let mut chars = word.chars();
+    if (i > 0 || capitalize_first)
+      && let Some(first) = chars.next()
+    {
+      camel.extend(first.to_uppercase());
+    }
+    camel.push_str(chars.as_str());
+  }
+  camel
+}
+
+/// The casing style an operation ID is written in, like `camelCase` for
+/// `getUsers`. Single lowercase words fit several styles and are
+/// `lowercase`, IDs fitting none are `mixed`.
+pub fn casing_of(opid: &str) -> &'static str {
+  let has_lower = opid.contains(char::is_lowercase);
+  let has_upper = opid.contains(char::is_uppercase);
+  match (opid.contains('_'), opid.contains('-')) {
+    (true, false) if !has_upper => "snake_case",
+    (true, false) if !has_lower => "SCREAMING_SNAKE_CASE",
+    (false, true) if !has_upper => "kebab-case",
+    (false, false) if !has_upper => "lowercase",
+    (false, false) if !has_lower => "UPPERCASE",
+    (false, false) if opid.starts_with(char::is_lowercase) => "camelCase",
+    (false, false) if opid.starts_with(char::is_uppercase) => "PascalCase",
+    _ => "mixed",
+  }
+}
+
+/// Split an operation ID into the namespace [`Settings::tag_namespace`]
+/// put it in and the rest, like `(Some("billing"), "invoices_get")` for
+/// `billing.invoices_get` and a `joiner` of `.`. Tags are sanitized, so the
+/// namespace never contains the joiner itself.
+pub fn split_namespace<'a>(opid: &'a str, joiner: &str) -> (Option<&'a str>, &'a str) {
+  match opid.split_once(joiner) {
+    Some((namespace, rest)) if !joiner.is_empty() => (Some(namespace), rest),
+    _ => (None, opid),
+  }
+}
+
+/// Where an operation ID came from
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Origin {
+  /// Spelled out by the spec
+  Explicit,
+  /// Assigned by the generator
+  Synthetic,
+}
+
+impl fmt::Display for Origin {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    f.write_str(match self {
+      Origin::Explicit => "explicit",
+      Origin::Synthetic => "synthetic",
+    })
+  }
+}
+
+/// Store for a one to one mapping between OAS operation IDs and
+/// path/method pairs. The store
+/// supports lookup in each direction.
+#[derive(Default, Debug)]
+pub struct OperationIds {
+  settings: Settings,
+  opid_to_path_method: BTreeMap<String, PathMethod>,
+  path_method_to_opid: BTreeMap<PathMethod, String>,
+  origins: BTreeMap<String, Origin>,
+  /// Sanitized path of the server URL, prepended to synthetic IDs
+  base_path: Option<String>,
+  collision_attempts: BTreeMap<String, u32>,
+  warnings: Vec<Warning>,
+}
+
+/// Split the greedy marker off a path parameter name, like the `+` of the
+/// `{proxy+}` catch-alls of API gateways, or the `*` of `{*path}`. Returns
+/// the bare name and whether it had a marker.
+fn strip_catch_all(param: &str) -> (&str, bool) {
+  let name = param.trim_end_matches(['+', '*']).trim_start_matches('*');
+  (name, name.len() != param.len())
+}
+
+/// Names of the path parameters of `path` that catch all the rest of the
+/// path, like `proxy` for `/api/{proxy+}`, without their marker
+fn catch_all_params(path: &str) -> Vec<&str> {
+  path
+    .split('{')
+    .skip(1)
+    .filter_map(|rest| rest.split_once('}'))
+    .map(|(param, _)| strip_catch_all(param))
+    .filter(|(name, catch_all)| *catch_all && !name.is_empty())
+    .map(|(name, _)| name)
+    .collect()
+}
+
+/// Extract path parameters (like {id}) from a path string
+/// and remove them from the path string. If parameters were found,
+/// return a vector of them and the modified path. Otherwise, [`None`]
+/// Greedy markers like the `+` of `{proxy+}` are no part of the name.
+pub fn extract_params(path: &str) -> Option<(Vec<&str>, String)> {
+  let mut params = Vec::new();
+  let mut clean_path = String::with_capacity(path.len());
+  let mut last_end = 0;
+  let mut in_param = false;
+  let mut param_start = 0;
+
+  for (i, c) in path.char_indices() {
+    if c == '{' && !in_param {
+      in_param = true;
+      clean_path.push_str(&path[last_end..i]);
+      clean_path.push_str("{}");
+      param_start = i + 1;
+    } else if c == '}' && in_param {
+      in_param = false;
+      let (param, _) = strip_catch_all(&path[param_start..i]);
+      if !param.is_empty() {
+        params.push(param);
+      }
+      last_end = i + 1;
+    }
+  }
+
+  // Add any remaining part after the last parameter
+  if last_end < path.len() {
+    clean_path.push_str(&path[last_end..]);
+  }
+
+  if params.is_empty() {
+    None
+  } else {
+    Some((params, clean_path))
+  }
+}
+
+impl OperationIds {
+  /// Create an empty store that assigns operation IDs according to the
+  /// given [`Settings`]
+  pub fn with_settings(settings: Settings) -> Self {
+    Self {
+      settings,
+      ..Self::default()
+    }
+  }
+
+  /// Create a store holding the given operation IDs as explicit ones, for
+  /// tests that need a populated store without a series of inserts. Fails
+  /// if two operation IDs share a key, since the store could not map the
+  /// key back to both of them.
+  #[cfg(test)]
+  pub fn from_mapping(map: BTreeMap<String, PathMethod>) -> Result<Self> {
+    let mut path_method_to_opid = BTreeMap::new();
+    for (opid, key) in &map {
+      if let Some(other) = path_method_to_opid.insert(key.clone(), opid.clone()) {
+        return Err(anyhow!(
+          "operation ids {other:?} and {opid:?} both map to {key}"
+        ));
+      }
+    }
+    let opids = Self {
+      origins: map
+        .keys()
+        .map(|opid| (opid.clone(), Origin::Explicit))
+        .collect(),
+      opid_to_path_method: map,
+      path_method_to_opid,
+      ..Self::default()
+    };
+    debug_assert!(opids
+      .opid_to_path_method
+      .iter()
+      .all(|(opid, key)| opids.path_method_to_opid.get(key) == Some(opid)));
+    Ok(opids)
+  }
+
+  /// Prepend the path of the server URL to synthetic operation IDs, like
+  /// `v2_users_get` for `https://api.example.com/v2`, so endpoints of specs
+  /// that are only told apart by their base path stay apart. Relative URLs
+  /// like `/v2` are fine too. A URL without a path changes nothing.
+  pub fn set_base_path(&mut self, server_url: &str) -> Result<()> {
+    let url = url::Url::parse(server_url)
+      .or_else(|_| url::Url::parse("http://localhost")?.join(server_url))
+      .map_err(|err| anyhow!("cannot parse server url {server_url:?}: {err}"))?;
+    let mut base_path = sanitize(url.path()).to_lowercase();
+    self.guard_leading_digit(&mut base_path);
+    self.base_path = Some(base_path).filter(|base_path| !base_path.is_empty());
+    Ok(())
+  }
+
+  /// Build the map key for a path and method. With
+  /// [`Settings::normalize_unicode`], the path is brought into normalization
+  /// form C, and with [`Settings::case_insensitive_paths`], it is lowercased
+  /// first so that paths differing only in case map to the same key.
+  fn key(&self, path: &str, method: &str) -> Result<PathMethod> {
+    let mut path = Cow::Borrowed(path);
+    if self.settings.normalize_unicode && !is_nfc(&path) {
+      path = Cow::Owned(path.nfc().collect());
+    }
+    if self.settings.case_insensitive_paths {
+      path = Cow::Owned(path.to_lowercase());
+    }
+
+    match extract_params(&path) {
+      Some((params, normalized_path)) => {
+        PathMethod::new(&normalized_path, method, Some(params))
+      }
+      None => PathMethod::new(path, method, None),
+    }
+  }
+
+  /// Find operation ID for given path and method. Returns [`None`] if
+  /// no operation ID was found
+  pub fn opid_for_path_method(&self, path: &str, method: &str) -> Option<&str> {
+    let key = self.key(path, method).ok()?;
+    self.path_method_to_opid.get(&key).map(|s| s.as_str())
+  }
+
+  /// Find path and method for a given operation ID. Returns [`None`] if
+  /// no path/method combination was found for the given operation ID
+  pub fn path_method_for_opid(&self, operation_id: &str) -> Option<(&str, &str)> {
+    self
+      .path_method(operation_id)
+      .map(|path_method| (path_method.path.as_str(), path_method.method.as_str()))
+  }
+
+  /// Generate a new operation ID candidate for the given PathMethod, considering
+  /// the number of attempts that have already been made. The number of attempts
+  /// is included in the candiate name (unless it is 0), to help resolve name
+  /// collisions.
+  /// For generated operation IDs that would start with a number,
+  /// the character 'n' is prepended.
+  ///
+  /// The operation_id names are created with the pattern
+  /// `converted_path [attempt] converted_method`.
+  ///
+  /// For `GET /foo/bar`, this will yield an operation ID of `foo_bar_get`.
+  /// It was deliberately chosen to have the method part at the end of the
+  /// generated operation ID string, so that the main point of destinction
+  /// is the path.
+  /// This is useful when the operation ID is used to
+  /// generate client method names: `foo_bar_get` and `foo_bar_post` will
+  /// be listed next to each other in a method name list.
+  ///
+  /// Without [`Settings::params_in_id`], the `_by_param` suffixes are left
+  /// out and paths only differing in their parameters are told apart by the
+  /// attempt number alone.
+  /// With [`Settings::minimal_suffix`], the caller tries the name without
+  /// them first.
+  ///
+  /// Paths without any words, like `/` or `/{id}`, are named
+  /// [`Settings::root_name`].
+  ///
+  /// With [`Settings::template`], the operation ID is rendered from the
+  /// template instead, see [`Self::render_template`].
+  ///
+  /// With a base path set by [`Self::set_base_path`], it is prepended to
+  /// the operation ID, like `v2_foo_bar_get`.
+  ///
+  /// Finally, digits are spelled out with [`Settings::spell_out_digits`],
+  /// words are respelled with [`Settings::word_exceptions`], and the
+  /// operation ID is brought into [`Settings::naming_style`]. With
+  /// [`Settings::tag_namespace`], the first tag is put in front of it.
+  fn gen_operation_id(
+    &self,
+    path_method: &PathMethod,
+    info: &OperationInfo,
+    attempt: u32,
+  ) -> String {
+    let opid = self.gen_unprefixed_operation_id(path_method, info, attempt);
+    let opid = match &self.base_path {
+      Some(base_path) => format!("{base_path}_{opid}"),
+      None => opid,
+    };
+    let opid = match self.settings.layout {
+      Layout::PathFirst => self.apply_style(opid),
+      Layout::MethodPrefix => format!(
+        "{}{}{}",
+        self.method_fragment(path_method).to_uppercase(),
+        self.settings.method_separator,
+        self.apply_style(opid)
+      ),
+    };
+    match (&self.settings.tag_namespace, &info.tag) {
+      (Some(joiner), Some(tag)) if !self.words(tag).is_empty() => {
+        format!("{}{joiner}{opid}", self.apply_style(self.words(tag)))
+      }
+      _ => opid,
+    }
+  }
+
+  /// Spell out the digits of the `_` separated `opid` with
+  /// [`Settings::spell_out_digits`], respell its words with
+  /// [`Settings::word_exceptions`], and bring it into
+  /// [`Settings::naming_style`]. Separators left at either end, like from an
+  /// empty [`Settings::root_name`] or a method alias ending in `_`, are
+  /// trimmed.
+  fn apply_style(&self, opid: String) -> String {
+    let mut opid = if self.settings.spell_out_digits {
+      spell_out_digits(&opid)
+    } else {
+      opid
+    };
+    if !self.settings.word_exceptions.is_empty() {
+      opid = opid
+        .split('_')
+        .map(|word| {
+          self
+            .settings
+            .word_exceptions
+            .get(&word.to_lowercase())
+            .map_or(word, String::as_str)
+        })
+        .collect::<Vec<_>>()
+        .join("_");
+    }
+    let opid = match self.settings.naming_style {
+      NamingStyle::Snake => opid,
+      NamingStyle::Kebab => opid.replace('_', "-"),
+      NamingStyle::Camel => camel_case(&opid, false),
+      NamingStyle::Pascal => camel_case(&opid, true),
+    };
+    match self.settings.naming_style.separator() {
+      Some(separator) => opid.trim_matches(separator).to_string(),
+      None => opid,
+    }
+  }
+
+  /// Bring an explicit operation ID in line with synthetic ones: split it
+  /// into lowercase words, also where the case changes, and join them in
+  /// [`Settings::naming_style`], so `getUsers` becomes `get_users`. The
+  /// `attempt` is appended like the collision suffix of synthetic IDs.
+  /// Returns [`None`] if no letters or digits are left.
+  fn sanitize_explicit_opid(&self, operation_id: &str, attempt: u32) -> Option<String> {
+    let mut opid = split_words(operation_id, &self.settings.acronyms).join("_");
+    if opid.is_empty() {
+      return None;
+    }
+    self.guard_leading_digit(&mut opid);
+    if attempt > 0 {
+      opid += &attempt.to_string();
+    }
+    Some(self.apply_style(opid))
+  }
+
+  /// [`Self::gen_operation_id`] without the base path and naming style
+  fn gen_unprefixed_operation_id(
+    &self,
+    path_method: &PathMethod,
+    info: &OperationInfo,
+    mut attempt: u32,
+  ) -> String {
+    if let Some(opid) = self
+      .settings
+      .template
+      .as_ref()
+      .and_then(|template| self.render_template(template, path_method, info, attempt))
+    {
+      return opid;
+    }
+
+    let mut path = self.naming_path(path_method);
+    if self.settings.last_segment_only {
+      // with parent segments, the attempt is spent on them first, and only
+      // what is left once the path runs out becomes the numeric suffix
+      let count = match self.settings.collision_strategy {
+        CollisionStrategy::Number => 1,
+        CollisionStrategy::ParentSegment => attempt as usize + 1,
+      };
+      let (segments, taken) = last_segments(&path, count);
+      if self.settings.collision_strategy == CollisionStrategy::ParentSegment {
+        attempt = (count - taken.max(1)) as u32;
+      }
+      path = Cow::Owned(segments);
+    }
+    let mut opid = match self.settings.intra_segment {
+      IntraSegment::Split => self.words(&path),
+      IntraSegment::Join => self.words(&join_segments(&path)),
+    };
+    if opid.is_empty() {
+      opid = self.words(&self.settings.root_name);
+    }
+    self.guard_leading_digit(&mut opid);
+
+    if attempt > 0 {
+      opid += &attempt.to_string();
+    }
+    let append_method = match self.settings.method_suffix {
+      _ if self.settings.layout == Layout::MethodPrefix => false,
+      MethodSuffix::Always => true,
+      MethodSuffix::Never => false,
+      MethodSuffix::Auto => info.shares_path,
+    };
+    if append_method {
+      opid += &format!("_{}", self.cased_method_fragment(path_method));
+    }
+
+    if let Some(params) = path_method
+      .params
+      .as_ref()
+      .filter(|_| self.settings.params_in_id)
+    {
+      params
+        .iter()
+        .enumerate()
+        // a name repeated by mistake, like in `/{id}/sub/{id}`, can be
+        // spelled out once
+        .filter(|(i, p)| !self.settings.dedupe_params || !params[..*i].contains(*p))
+        .for_each(|(_, p)| {
+          let param = match self.settings.naming_style {
+            NamingStyle::Snake | NamingStyle::Kebab => p.to_lowercase(),
+            NamingStyle::Camel | NamingStyle::Pascal => self.words(p),
+          };
+          opid += &format!("_by_{param}");
+          if info.catch_all.contains(p) {
+            opid += "_catchall";
+          }
+        });
+    }
+    if let Some(discriminator) = &info.discriminator {
+      let discriminator = self.words(discriminator);
+      if !discriminator.is_empty() {
+        opid += &format!("_{discriminator}");
+      }
+    }
+    opid
+  }
+
+  /// `s` as lowercase words joined by `_`. For [`NamingStyle::Camel`] and
+  /// [`NamingStyle::Pascal`], words are split where the case changes as
+  /// well, see [`split_words`].
+  fn words(&self, s: &str) -> String {
+    match self.settings.naming_style {
+      NamingStyle::Snake | NamingStyle::Kebab => sanitize(s).to_lowercase(),
+      NamingStyle::Camel | NamingStyle::Pascal => {
+        split_words(s, &self.settings.acronyms).join("_")
+      }
+    }
+  }
+
+  /// Prepend an `n` to an operation ID starting with a digit, which would
+  /// not make for a valid identifier. Not needed if digits are spelled out.
+  fn guard_leading_digit(&self, opid: &mut String) {
+    if !self.settings.spell_out_digits && opid.starts_with(char::is_numeric) {
+      opid.insert(0, 'n');
+    }
+  }
+
+  /// The word for the method in operation IDs: its
+  /// [`Settings::method_aliases`] entry, or the lowercase method itself
+  fn method_fragment<'a>(&'a self, path_method: &'a PathMethod) -> &'a str {
+    self
+      .settings
+      .method_aliases
+      .get(&path_method.method)
+      .unwrap_or(&path_method.method)
+  }
+
+  /// [`Self::method_fragment`] in the [`Settings::method_case`]. Upper case
+  /// methods are kept as they are by the naming style, like `usersGET`.
+  fn cased_method_fragment<'a>(&'a self, path_method: &'a PathMethod) -> Cow<'a, str> {
+    let fragment = self.method_fragment(path_method);
+    match self.settings.method_case {
+      MethodCase::Lower => Cow::Owned(fragment.to_lowercase()),
+      MethodCase::Upper => Cow::Owned(fragment.to_uppercase()),
+      MethodCase::Preserve => Cow::Borrowed(fragment),
+    }
+  }
+
+  /// The path operation IDs are derived from. With
+  /// [`Settings::decode_paths`], percent-encoded characters are decoded and
+  /// `+` becomes a space first, and with [`Settings::strip_extension`], the
+  /// file extension of the last segment is dropped. The path stored in the
+  /// key stays as it is.
+  fn naming_path<'a>(&self, path_method: &'a PathMethod) -> Cow<'a, str> {
+    let path = if self.settings.decode_paths {
+      Cow::Owned(percent_decode(&path_method.path))
+    } else {
+      Cow::Borrowed(path_method.path.as_str())
+    };
+    if !self.settings.strip_extension {
+      return path;
+    }
+    match path {
+      Cow::Borrowed(path) => Cow::Borrowed(strip_extension(path)),
+      Cow::Owned(path) => Cow::Owned(strip_extension(&path).to_string()),
+    }
+  }
+
+  /// Render an operation ID from a template like `{tag}_{summary}`. The
+  /// placeholders resolve to
+  ///
+  /// - `{path}`: the path with its parameters stripped, as used by default
+  /// - `{method}`: the lowercase method, or its alias
+  /// - `{tag}`: the first tag of the operation
+  /// - `{summary}`: the summary of the operation
+  /// - `{params}`: the path parameters, like `by_id_by_name`
+  /// - `{attempt}`: the collision suffix. If the template does not place it,
+  ///   it is appended to the end.
+  ///
+  /// Placeholders for fields the operation lacks resolve to nothing. The
+  /// rendered template is sanitized like generated operation IDs by default:
+  /// everything but letters and digits becomes `_`, the result is lowercased,
+  /// and an `n` is prepended if it starts with a digit. Returns [`None`] if
+  /// nothing is left after sanitizing.
+  fn render_template(
+    &self,
+    template: &str,
+    path_method: &PathMethod,
+    info: &OperationInfo,
+    attempt: u32,
+  ) -> Option<String> {
+    let path = sanitize(&self.naming_path(path_method));
+    let params = path_method
+      .params
+      .iter()
+      .flatten()
+      .map(|param| format!("by_{param}"))
+      .collect::<Vec<_>>()
+      .join("_");
+    let attempt = if attempt == 0 {
+      String::new()
+    } else {
+      attempt.to_string()
+    };
+
+    let mut rendered = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(start) = rest.find('{') {
+      let Some(end) = rest[start..].find('}').map(|end| start + end) else {
+        break;
+      };
+      rendered.push_str(&rest[..start]);
+      rendered.push_str(match &rest[start + 1..end] {
+        "path" => path.as_str(),
+        "method" => self.method_fragment(path_method),
+        "tag" => info.tag.as_deref().unwrap_or_default(),
+        "summary" => info.summary.as_deref().unwrap_or_default(),
+        "params" => params.as_str(),
+        "attempt" => attempt.as_str(),
+        _ => &rest[start..=end],
+      });
+      rest = &rest[end + 1..];
+    }
+    rendered.push_str(rest);
+
+    let mut opid = sanitize(&rendered);
+    if opid.is_empty() {
+      return None;
+    }
+    if !template.contains("{attempt}") {
+      opid += &attempt;
+    }
+    opid = opid.to_lowercase();
+    self.guard_leading_digit(&mut opid);
+    Some(opid)
+  }
+
+  /// Insert a new operation ID with with it's path and method attached.
+  /// The method will fail if the operation ID, or the path and method
+  /// combination already exist in this [`OperationIds`] instance.
+  /// With [`Settings::case_insensitive_paths`], inserting the same operation
+  /// ID again for a path differing only in case merges the two endpoints,
+  /// while a different operation ID is a conflict.
+  pub fn insert_opid_with_path_method(
+    &mut self,
+    operation_id: &str,
+    path: &str,
+    method: &str,
+  ) -> Result<()> {
+    let key = self.key(path, method)?;
+
+    if self.settings.case_insensitive_paths
+      && let Some(existing) = self.path_method_to_opid.get(&key)
+    {
+      if existing == operation_id {
+        return Ok(());
+      }
+      return Err(anyhow!(
+        "conflicting operation ids {existing:?} and {operation_id:?} for path {path} \
+         and method {method}, which only differ in case"
+      ));
+    }
+
+    if let Some(existing) = self.opid_to_path_method.get(operation_id) {
+      if existing.is_reserved() {
+        return Err(anyhow!("operation id is reserved: {operation_id:?}"));
+      }
+      return Err(anyhow!("operation id is already present: {operation_id:?}"));
+    }
+    if self.path_method_to_opid.contains_key(&key) {
+      return Err(anyhow!("the endpoint {key} is already present"));
+    }
+
+    if self.settings.reserved_names.contains(operation_id) {
+      self.warnings.push(Warning::new(format!(
+        "operation id {operation_id:?} of {} {path} is reserved and may shadow \
+         a helper of the generated client",
+        method.to_uppercase()
+      )));
+    }
+
+    self
+      .opid_to_path_method
+      .insert(operation_id.to_string(), key.clone());
+    self
+      .path_method_to_opid
+      .insert(key, operation_id.to_string());
+    self
+      .origins
+      .insert(operation_id.to_string(), Origin::Explicit);
+    Ok(())
+  }
+
+  /// Insert an explicit operation ID sanitized like synthetic ones are,
+  /// see [`Settings::sanitize_explicit`]. If the sanitized operation ID is
+  /// taken, a collision suffix is appended. Returns the inserted operation
+  /// ID.
+  pub fn insert_sanitized_opid_with_path_method(
+    &mut self,
+    operation_id: &str,
+    path: &str,
+    method: &str,
+  ) -> Result<String> {
+    let mut attempt = 0;
+    let candidate = loop {
+      let candidate = self
+        .sanitize_explicit_opid(operation_id, attempt)
+        .ok_or_else(|| {
+          anyhow!("operation id {operation_id:?} has no letters or digits to keep")
+        })?;
+      if !self.opid_to_path_method.contains_key(&candidate) {
+        break candidate;
+      }
+      attempt += 1;
+    };
+    self.insert_opid_with_path_method(&candidate, path, method)?;
+    Ok(candidate)
+  }
+
+  /// Insert an operation ID that was picked for the endpoint by other means
+  /// than the spec, like a [`crate::strategy::IdStrategy`]. It fails like
+  /// [`Self::insert_opid_with_path_method`] does, but the operation ID
+  /// counts as synthetic.
+  pub fn insert_chosen_opid_for_path_method(
+    &mut self,
+    operation_id: &str,
+    path: &str,
+    method: &str,
+  ) -> Result<()> {
+    let known = self
+      .path_method_to_opid
+      .contains_key(&self.key(path, method)?);
+    self.insert_opid_with_path_method(operation_id, path, method)?;
+    if !known {
+      self
+        .origins
+        .insert(operation_id.to_string(), Origin::Synthetic);
+      self.collision_attempts.insert(operation_id.to_string(), 0);
+    }
+    Ok(())
+  }
+
+  /// Reserve an operation ID without assigning it to an endpoint, for
+  /// example for a method that is added to the client by hand. Synthetic
+  /// operation IDs skip reserved ones, and inserting it as an explicit
+  /// operation ID fails. Fails if the operation ID is already present.
+  pub fn reserve(&mut self, operation_id: &str) -> Result<()> {
+    if self.opid_to_path_method.contains_key(operation_id) {
+      return Err(anyhow!("operation id is already present: {operation_id:?}"));
+    }
+    self
+      .opid_to_path_method
+      .insert(operation_id.to_string(), PathMethod::reserved());
+    Ok(())
+  }
+
+  /// Insert a generated opid for the given path and method combination.
+  /// The method will choose an operation ID that does not collide
+  /// with pre existing operation IDs in this [`OperationIds`] instance,
+  /// nor with any of the [`Settings::reserved_names`].
+  /// The method will fail if the given path and methoc combination already
+  /// exists, unless [`Settings::case_insensitive_paths`] is set: then the
+  /// operation ID already assigned to the path and method is returned.
+  /// Returns synthetic operation ID
+  pub fn insert_synthetic_opid_for_path_method(
+    &mut self,
+    path: &str,
+    method: &str,
+  ) -> Result<String> {
+    self.insert_synthetic_opid_for_operation(path, method, &OperationInfo::default())
+  }
+
+  /// Like [`Self::insert_synthetic_opid_for_path_method`], but with details
+  /// of the operation at hand for [`Settings::template`] to draw from
+  pub fn insert_synthetic_opid_for_operation(
+    &mut self,
+    path: &str,
+    method: &str,
+    info: &OperationInfo,
+  ) -> Result<String> {
+    let key = self.key(path, method)?;
+    let catch_all_info;
+    let info = if self.settings.mark_catch_all {
+      catch_all_info = OperationInfo {
+        catch_all: catch_all_params(path)
+          .into_iter()
+          .map(String::from)
+          .collect(),
+        ..info.clone()
+      };
+      &catch_all_info
+    } else {
+      info
+    };
+
+    if self.settings.case_insensitive_paths
+      && let Some(existing) = self.path_method_to_opid.get(&key)
+    {
+      return Ok(existing.clone());
+    }
+    if self.path_method_to_opid.contains_key(&key) {
+      return Err(anyhow!("the endpoint {key} is already present"));
+    }
+
+    let is_free = |candidate: &String| {
+      !self.opid_to_path_method.contains_key(candidate)
+        && !self.settings.reserved_names.contains(candidate)
+    };
+    // In minimal suffix mode, the name without parameters is tried first,
+    // and the parameters are only added to resolve a collision
+    let without_params = Some(&key)
+      .filter(|key| self.settings.minimal_suffix && key.params.is_some())
+      .map(|key| PathMethod {
+        params: None,
+        ..key.clone()
+      })
+      .map(|path_method| self.gen_operation_id(&path_method, info, 0))
+      .filter(|candidate| is_free(candidate));
+    // Past the summarizing threshold, the parameters are only counted, like
+    // `users_get_by_6_params`, and listed in full if that collides
+    let summarized = key
+      .params
+      .as_ref()
+      .filter(|params| {
+        self
+          .settings
+          .summarize_params_above
+          .is_some_and(|max| params.len() > max)
+      })
+      .map(|params| PathMethod {
+        params: Some(vec![format!("{}_params", params.len())]),
+        ..key.clone()
+      })
+      .map(|path_method| self.gen_operation_id(&path_method, info, 0))
+      .filter(|candidate| is_free(candidate));
+
+    let mut attempt = 0;
+    let candidate = match without_params.or(summarized) {
+      Some(candidate) => candidate,
+      None => loop {
+        let candidate = self.gen_operation_id(&key, info, attempt);
+        if is_free(&candidate) {
+          break candidate;
+        }
+        if self.settings.method_suffix == MethodSuffix::Never
+          && self.settings.layout == Layout::PathFirst
+        {
+          return Err(anyhow!(
+            "operation id {candidate:?} for {key} is taken, and without the \
+             method suffix collisions are not resolved"
+          ));
+        }
+        attempt += 1;
+      },
+    };
+
+    // Paths made of slashes and parameters only, like the root, are fine,
+    // but other punctuation without any letters is most likely a typo
+    let punctuation = self.naming_path(&key).replace("{}", "").replace('/', "");
+
+    self
+      .path_method_to_opid
+      .insert(key.clone(), candidate.clone());
+    self.opid_to_path_method.insert(candidate.clone(), key);
+    self.origins.insert(candidate.clone(), Origin::Synthetic);
+    self.collision_attempts.insert(candidate.clone(), attempt);
+
+    if !punctuation.is_empty() && !punctuation.contains(char::is_alphanumeric) {
+      self.warnings.push(Warning::new(format!(
+        "path {path} of {} contains no letters or digits, so operation id \
+         {candidate:?} does not tell anything about it",
+        method.to_uppercase()
+      )));
+    }
+    let length = candidate.chars().count();
+    if length > self.settings.warn_id_length {
+      self.warnings.push(Warning::new(format!(
+        "operation id {candidate:?} of {} {path} is {length} characters long, \
+         more than the recommended {}",
+        method.to_uppercase(),
+        self.settings.warn_id_length
+      )));
+    }
+    Ok(candidate)
+  }
+
+  /// The attempt every synthetic operation ID settled on, keyed by
+  /// operation ID. `0` means the first candidate was free, higher numbers
+  /// are the collision suffix the ID carries. Many high numbers hint at a
+  /// lot of paths that look alike.
+  pub fn collision_attempts(&self) -> &BTreeMap<String, u32> {
+    &self.collision_attempts
+  }
+
+  /// Whether the operation ID was spelled out by the spec or generated.
+  /// Returns [`None`] for unknown and reserved operation IDs.
+  pub fn origin_of(&self, operation_id: &str) -> Option<Origin> {
+    self.origins.get(operation_id).copied()
+  }
+
+  /// Summary of the operation IDs in this store
+  pub fn stats(&self) -> Stats {
+    let count = |origin| {
+      self
+        .origins
+        .values()
+        .filter(|other| **other == origin)
+        .count()
+    };
+    Stats {
+      explicit: count(Origin::Explicit),
+      synthetic: count(Origin::Synthetic),
+      collisions: self
+        .collision_attempts
+        .values()
+        .filter(|attempt| **attempt > 0)
+        .count(),
+      max_attempt: self.collision_attempts.values().copied().max().unwrap_or(0),
+    }
+  }
+
+  /// Find the path/method combination for a given operation ID
+  pub fn path_method(&self, operation_id: &str) -> Option<&PathMethod> {
+    self
+      .opid_to_path_method
+      .get(operation_id)
+      .filter(|path_method| !path_method.is_reserved())
+  }
+
+  /// Iterate over all operation IDs and their path/method, sorted by
+  /// operation ID. Reserved operation IDs are skipped.
+  pub fn iter(&self) -> impl Iterator<Item = (&str, &PathMethod)> {
+    self
+      .opid_to_path_method
+      .iter()
+      .filter(|(_, path_method)| !path_method.is_reserved())
+      .map(|(opid, path_method)| (opid.as_str(), path_method))
+  }
+
+  /// Warnings collected while inserting operation IDs
+  pub fn warnings(&self) -> &[Warning] {
+    &self.warnings
+  }
+
+  /// Record a warning about the spec the operation IDs are assigned for
+  pub fn add_warning(&mut self, warning: Warning) {
+    self.warnings.push(warning);
+  }
+
+  /// Synthetic operation IDs along with their keys, sorted by key
+  pub fn synthetic_endpoints(&self) -> impl Iterator<Item = (&PathMethod, &str)> {
+    self
+      .path_method_to_opid
+      .iter()
+      .filter(|(_, opid)| self.origin_of(opid) == Some(Origin::Synthetic))
+      .map(|(key, opid)| (key, opid.as_str()))
+  }
+
+  /// Both internal maps in readable form, sorted, for tracking down why an
+  /// operation ID came out the way it did. Every operation ID comes with
+  /// its origin and the collision attempt it settled on, reserved ones are
+  /// marked as such. Checks that the maps are inverses of each other.
+  pub fn dump(&self) -> String {
+    let mut dump = String::from("operation ids:\n");
+    for (opid, key) in &self.opid_to_path_method {
+      if key.is_reserved() {
+        dump += &format!("  {opid} -> (reserved)\n");
+        continue;
+      }
+      assert_eq!(
+        self.path_method_to_opid.get(key),
+        Some(opid),
+        "operation id maps are out of sync for {opid:?}"
+      );
+      let origin = self
+        .origin_of(opid)
+        .map_or("unknown".to_string(), |origin| origin.to_string());
+      dump += &format!("  {opid} -> {key} ({origin}");
+      if let Some(attempt) = self.collision_attempts.get(opid) {
+        dump += &format!(", attempt {attempt}");
+      }
+      dump += ")\n";
+    }
+    dump += "endpoints:\n";
+    for (key, opid) in &self.path_method_to_opid {
+      assert_eq!(
+        self.opid_to_path_method.get(opid),
+        Some(key),
+        "operation id maps are out of sync for {key}"
+      );
+      dump += &format!("  {key} -> {opid}\n");
+    }
+    dump
+  }
+
+  /// Report of all synthetic operation IDs, one `METHOD /path -> opid` line
+  /// per endpoint
+  pub fn report(&self) -> String {
+    self
+      .synthetic_endpoints()
+      .map(|(key, opid)| format!("{key} -> {opid}\n"))
+      .collect()
+  }
+
+  /// Report of all synthetic operation IDs grouped by path, the way the
+  /// spec organizes them: every path is printed once, with its methods and
+  /// their operation IDs indented beneath it
+  pub fn report_grouped(&self) -> String {
+    let mut groups: BTreeMap<String, Vec<(&str, &str)>> = BTreeMap::new();
+    self.synthetic_endpoints().for_each(|(key, opid)| {
+      groups
+        .entry(key.path_with_params())
+        .or_default()
+        .push((key.method.as_str(), opid));
+    });
+
+    let mut report = String::new();
+    for (path, endpoints) in groups {
+      report += &format!("{path}\n");
+      for (method, opid) in endpoints {
+        report += &format!("  {} -> {opid}\n", method.to_uppercase());
+      }
+    }
+    report
+  }
+
+  /// Report of the casing styles the explicit operation IDs are written in,
+  /// one line per style with the number of IDs and an example, for
+  /// deciding whether to normalize them with
+  /// [`Settings::sanitize_explicit`]. Empty without explicit IDs.
+  pub fn casing_mix(&self) -> String {
+    let mut styles: BTreeMap<&str, (usize, &str)> = BTreeMap::new();
+    self
+      .opid_to_path_method
+      .keys()
+      .filter(|opid| self.origin_of(opid) == Some(Origin::Explicit))
+      .for_each(|opid| {
+        styles
+          .entry(casing_of(opid))
+          .or_insert((0, opid.as_str()))
+          .0 += 1;
+      });
+    if styles.is_empty() {
+      return String::new();
+    }
+
+    let mut report = format!(
+      "explicit operation ids use {} casing style{}:\n",
+      styles.len(),
+      if styles.len() == 1 { "" } else { "s" }
+    );
+    for (style, (count, example)) in styles {
+      report += &format!("  {style}: {count}, like {example}\n");
+    }
+    report
+  }
+
+  /// Histogram of the lengths of the synthetic operation IDs, in buckets
+  /// of ten characters, for picking a sensible
+  /// [`Settings::warn_id_length`]. Buckets between the shortest and the
+  /// longest operation ID are listed even if they are empty.
+  pub fn length_histogram(&self) -> String {
+    let mut buckets: BTreeMap<usize, usize> = BTreeMap::new();
+    self.synthetic_endpoints().for_each(|(_, opid)| {
+      *buckets.entry(opid.chars().count() / 10).or_default() += 1;
+    });
+    let (Some(first), Some(last)) = (
+      buckets.keys().next().copied(),
+      buckets.keys().next_back().copied(),
+    ) else {
+      return String::new();
+    };
+    let most = buckets.values().copied().max().unwrap_or_default();
+
+    let mut histogram = String::from("operation id lengths:\n");
+    for bucket in first..=last {
+      let count = buckets.get(&bucket).copied().unwrap_or_default();
+      // bars are scaled down once they would get unwieldy
+      let bar = count * 40 / most.max(40);
+      let line = format!(
+        "  {:>3}-{:<3} {count:>5} {}",
+        bucket * 10,
+        bucket * 10 + 9,
+        "#".repeat(bar.max(usize::from(count > 0)))
+      );
+      histogram += line.trim_end();
+      histogram.push('\n');
+    }
+    histogram
+  }
+}
+
+#[cfg(test)]
+fn mk_pm(path: &str, method: &str) -> PathMethod {
+  PathMethod::new(path, method, None).unwrap()
+}
+
+/// The synthetic operation ID for `method` on `path` with `settings`, when
+/// nothing else is taken yet
+#[cfg(test)]
+fn synthetic(settings: Settings, path: &str, method: &str) -> String {
+  OperationIds::with_settings(settings)
+    .insert_synthetic_opid_for_path_method(path, method)
+    .unwrap()
+}
+
+#[test]
+fn test_from_mapping() {
+  let opids = OperationIds::from_mapping(
+    [
+      ("list_users", mk_pm("/users", "get")),
+      ("create_user", mk_pm("/users", "post")),
+    ]
+    .map(|(opid, key)| (opid.to_string(), key))
+    .into(),
+  )
+  .unwrap();
+  assert_eq!(
+    opids.opid_for_path_method("/users", "get"),
+    Some("list_users")
+  );
+  assert_eq!(
+    opids.path_method_for_opid("create_user"),
+    Some(("/users", "post"))
+  );
+  assert_eq!(opids.origin_of("list_users"), Some(Origin::Explicit));
+  assert_eq!(opids.iter().count(), 2);
+
+  let err = OperationIds::from_mapping(
+    [
+      ("list_users", mk_pm("/users", "get")),
+      ("get_users", mk_pm("/users", "GET")),
+    ]
+    .map(|(opid, key)| (opid.to_string(), key))
+    .into(),
+  )
+  .unwrap_err();
+  assert_eq!(
+    err.to_string(),
+    "operation ids \"get_users\" and \"list_users\" both map to GET /users"
+  );
+}
+
+#[test]
+fn test_extract_params() {
+  assert_eq!(extract_params("/foo/bar"), None);
+  assert_eq!(
+    extract_params("/foo/{bar}/baz"),
+    Some((vec!["bar"], String::from("/foo/{}/baz")))
+  );
+  assert_eq!(
+    extract_params("/foo/{bar}/baz/{quux}"),
+    Some((vec!["bar", "quux"], String::from("/foo/{}/baz/{}")))
+  );
+  assert_eq!(
+    extract_params("/{foo}/{bar}"),
+    Some((vec!["foo", "bar"], String::from("/{}/{}")))
+  );
+  assert_eq!(
+    extract_params("/api/{proxy+}"),
+    Some((vec!["proxy"], String::from("/api/{}")))
+  );
+  assert_eq!(
+    extract_params("/files/{*path}"),
+    Some((vec!["path"], String::from("/files/{}")))
+  );
+}
+
+#[test]
+fn test_operation_id_generation() {
+  let info = OperationInfo::default();
+  assert_eq!(
+    OperationIds::default().gen_operation_id(&mk_pm("/foo/bar", "get"), &info, 0),
+    "foo_bar_get"
+  );
+  assert_eq!(
+    OperationIds::default().gen_operation_id(&mk_pm("/foo/bar", "get"), &info, 1),
+    "foo_bar1_get"
+  );
+  assert_eq!(
+    OperationIds::default().gen_operation_id(&mk_pm("/some.json", "get"), &info, 0),
+    "some_json_get"
+  );
+}
+
+#[test]
+fn test_operation_ids() {
+  let mut opids = OperationIds::default();
+
+  // insert
+  opids
+    .insert_opid_with_path_method("foo_get", "/foo", "get")
+    .unwrap();
+  assert_eq!(opids.opid_for_path_method("/foo", "get"), Some("foo_get"));
+  assert_eq!(opids.path_method_for_opid("foo_get"), Some(("/foo", "get")));
+  opids
+    .insert_opid_with_path_method("foo_post", "/foo", "post")
+    .unwrap();
+  assert_eq!(opids.opid_for_path_method("/foo", "post"), Some("foo_post"));
+  assert_eq!(
+    opids.path_method_for_opid("foo_post"),
+    Some(("/foo", "post"))
+  );
+
+  // insert must fail because of collision with operation id
+  assert!(opids
+    .insert_opid_with_path_method("foo_get", "/bar", "get")
+    .is_err());
+
+  // insert must fail because of collision with path and method
+  assert!(opids
+    .insert_opid_with_path_method("bar_get", "/foo", "get")
+    .is_err());
+
+  // now check we can create synthetic operation ids:
+  assert!(opids
+    .insert_synthetic_opid_for_path_method("/bar", "get")
+    .is_ok());
+  assert_eq!(opids.opid_for_path_method("/bar", "get"), Some("bar_get"));
+  assert_eq!(opids.path_method_for_opid("bar_get"), Some(("/bar", "get")));
+  assert!(opids
+    .insert_synthetic_opid_for_path_method("/bar", "post")
+    .is_ok());
+  assert_eq!(opids.opid_for_path_method("/bar", "post"), Some("bar_post"));
+  assert_eq!(
+    opids.path_method_for_opid("bar_post"),
+    Some(("/bar", "post"))
+  );
+
+  // test collisions.
+  // we're going to collide with foo_bar_get
+  opids
+    .insert_opid_with_path_method("foo_bar_get", "/foobar", "get")
+    .unwrap();
+  opids
+    .insert_synthetic_opid_for_path_method("/foo/bar", "get")
+    .unwrap();
+  assert_eq!(
+    opids.opid_for_path_method("/foo/bar", "get"),
+    Some("foo_bar1_get")
+  );
+  assert_eq!(
+    opids.path_method_for_opid("foo_bar1_get"),
+    Some(("/foo/bar", "get"))
+  );
+}
+
+#[test]
+fn test_case_insensitive_paths() {
+  let mut opids = OperationIds::with_settings(Settings {
+    case_insensitive_paths: true,
+    ..Settings::default()
+  });
+
+  // same explicit id on paths only differing in case is merged
+  opids
+    .insert_opid_with_path_method("list_users", "/Users", "get")
+    .unwrap();
+  opids
+    .insert_opid_with_path_method("list_users", "/users", "get")
+    .unwrap();
+  assert_eq!(
+    opids.opid_for_path_method("/USERS", "get"),
+    Some("list_users")
+  );
+
+  // a synthetic id for such a path is merged into the existing one
+  assert_eq!(
+    opids
+      .insert_synthetic_opid_for_path_method("/uSers", "get")
+      .unwrap(),
+    "list_users"
+  );
+
+  // conflicting explicit ids are an error
+  assert!(opids
+    .insert_opid_with_path_method("get_users", "/USERS", "get")
+    .is_err());
+
+  // by default, case matters
+  let mut opids = OperationIds::default();
+  opids
+    .insert_opid_with_path_method("list_users", "/Users", "get")
+    .unwrap();
+  assert_eq!(opids.opid_for_path_method("/users", "get"), None);
+  assert_eq!(
+    opids
+      .insert_synthetic_opid_for_path_method("/users", "get")
+      .unwrap(),
+    "users_get"
+  );
+}
+
+#[test]
+fn test_report() {
+  let mut opids = OperationIds::default();
+  opids
+    .insert_opid_with_path_method("list_users", "/users", "get")
+    .unwrap();
+  opids
+    .insert_synthetic_opid_for_path_method("/users", "post")
+    .unwrap();
+  opids
+    .insert_synthetic_opid_for_path_method("/users/{id}", "get")
+    .unwrap();
+  opids
+    .insert_synthetic_opid_for_path_method("/users/{id}", "delete")
+    .unwrap();
+
+  assert_eq!(
+    opids.report(),
+    "POST /users -> users_post\n\
+     DELETE /users/{id} -> users_delete_by_id\n\
+     GET /users/{id} -> users_get_by_id\n"
+  );
+  assert_eq!(
+    opids.report_grouped(),
+    "/users\n\
+     \x20 POST -> users_post\n\
+     /users/{id}\n\
+     \x20 DELETE -> users_delete_by_id\n\
+     \x20 GET -> users_get_by_id\n"
+  );
+}
+
+#[test]
+fn test_reserved_names() {
+  let mut opids = OperationIds::with_settings(Settings {
+    reserved_names: ["new_post".to_string()].into(),
+    ..Settings::default()
+  });
+
+  // synthetic ids step around reserved names
+  assert_eq!(
+    opids
+      .insert_synthetic_opid_for_path_method("/new", "post")
+      .unwrap(),
+    "new1_post"
+  );
+  assert!(opids.warnings().is_empty());
+
+  // explicit ids are kept, but warned about
+  opids
+    .insert_opid_with_path_method("new_post", "/items", "post")
+    .unwrap();
+  assert_eq!(
+    opids.opid_for_path_method("/items", "post"),
+    Some("new_post")
+  );
+  assert_eq!(
+    opids.warnings(),
+    [Warning::new(
+      "operation id \"new_post\" of POST /items is reserved and may shadow \
+       a helper of the generated client"
+    )]
+  );
+
+  // the defaults cover the helpers on progenitor's client
+  let mut opids = OperationIds::default();
+  opids
+    .insert_opid_with_path_method("new", "/new", "post")
+    .unwrap();
+  assert_eq!(opids.warnings().len(), 1);
+}
+
+#[test]
+fn test_origin_of() {
+  let mut opids = OperationIds::default();
+  opids
+    .insert_opid_with_path_method("list_users", "/users", "get")
+    .unwrap();
+  let synthetic = opids
+    .insert_synthetic_opid_for_path_method("/users", "post")
+    .unwrap();
+  opids
+    .insert_chosen_opid_for_path_method("remove_user", "/users/{id}", "delete")
+    .unwrap();
+  opids.reserve("special").unwrap();
+
+  assert_eq!(opids.origin_of("list_users"), Some(Origin::Explicit));
+  assert_eq!(opids.origin_of(&synthetic), Some(Origin::Synthetic));
+  assert_eq!(opids.origin_of("remove_user"), Some(Origin::Synthetic));
+  assert_eq!(opids.origin_of("special"), None);
+  assert_eq!(opids.origin_of("users_get"), None);
+}
+
+#[test]
+fn test_collision_attempts() {
+  let mut opids = OperationIds::default();
+  opids
+    .insert_opid_with_path_method("foo_bar_get", "/foo_bar", "get")
+    .unwrap();
+  opids
+    .insert_opid_with_path_method("foo_bar1_get", "/foo-bar", "get")
+    .unwrap();
+  opids
+    .insert_synthetic_opid_for_path_method("/foo/bar", "get")
+    .unwrap();
+  opids
+    .insert_synthetic_opid_for_path_method("/foo/bar", "post")
+    .unwrap();
+
+  assert_eq!(
+    opids.collision_attempts(),
+    &BTreeMap::from([
+      ("foo_bar2_get".to_string(), 2),
+      ("foo_bar_post".to_string(), 0)
+    ])
+  );
+  assert_eq!(
+    opids.stats(),
+    Stats {
+      explicit: 2,
+      synthetic: 2,
+      collisions: 1,
+      max_attempt: 2,
+    }
+  );
+}
+
+#[test]
+fn test_templates() {
+  let opid = |template: &str, path: &str, method: &str, info: &OperationInfo| {
+    let opids = OperationIds::with_settings(Settings {
+      template: Some(template.to_string()),
+      ..Settings::default()
+    });
+    let key = opids.key(path, method).unwrap();
+    (
+      opids.gen_operation_id(&key, info, 0),
+      opids.gen_operation_id(&key, info, 1),
+    )
+  };
+  let tagged = OperationInfo {
+    tag: Some("Users".to_string()),
+    summary: Some("List all users.".to_string()),
+    ..OperationInfo::default()
+  };
+  let untagged = OperationInfo::default();
+
+  // the default naming, spelled out as a template
+  let default = "{path}{attempt}_{method}_{params}";
+  let default_opids = OperationIds::default();
+  for (path, method) in [
+    ("/foo/bar", "get"),
+    ("/users/{id}", "delete"),
+    ("/2fa", "post"),
+  ] {
+    let key = default_opids.key(path, method).unwrap();
+    assert_eq!(
+      opid(default, path, method, &untagged),
+      (
+        default_opids.gen_operation_id(&key, &untagged, 0),
+        default_opids.gen_operation_id(&key, &untagged, 1),
+      )
+    );
+  }
+
+  assert_eq!(
+    opid("{tag}_{summary}", "/users", "get", &tagged),
+    (
+      "users_list_all_users".to_string(),
+      "users_list_all_users1".to_string()
+    )
+  );
+  assert_eq!(
+    opid("{method}_{path}_{params}", "/users/{id}", "get", &tagged),
+    (
+      "get_users_by_id".to_string(),
+      "get_users_by_id1".to_string()
+    )
+  );
+  // missing fields resolve to nothing
+  assert_eq!(
+    opid("{tag}_{summary}_{method}", "/users", "get", &untagged),
+    ("get".to_string(), "get1".to_string())
+  );
+  // a template rendering to nothing falls back to the default naming
+  assert_eq!(
+    opid("{tag}", "/users", "get", &untagged),
+    ("users_get".to_string(), "users1_get".to_string())
+  );
+
+  assert!(check_template("{tag}_{summary}").is_ok());
+  assert!(check_template("{tags}").is_err());
+  assert!(check_template("{path").is_err());
+}
+
+#[test]
+fn test_no_params_in_id() {
+  let mut opids = OperationIds::with_settings(Settings {
+    params_in_id: false,
+    ..Settings::default()
+  });
+
+  assert_eq!(
+    opids
+      .insert_synthetic_opid_for_path_method("/users", "get")
+      .unwrap(),
+    "users_get"
+  );
+  // collapses to `users_get` as well, and needs a suffix
+  assert_eq!(
+    opids
+      .insert_synthetic_opid_for_path_method("/users/{id}", "get")
+      .unwrap(),
+    "users1_get"
+  );
+  assert_eq!(
+    opids
+      .insert_synthetic_opid_for_path_method("/users/{id}", "delete")
+      .unwrap(),
+    "users_delete"
+  );
+  assert_eq!(
+    opids.opid_for_path_method("/users", "get"),
+    Some("users_get")
+  );
+  assert_eq!(
+    opids.opid_for_path_method("/users/{id}", "get"),
+    Some("users1_get")
+  );
+}
+
+#[test]
+fn test_control_characters() {
+  let mut opids = OperationIds::default();
+  let err = opids
+    .insert_synthetic_opid_for_path_method("/users\0/admin", "get")
+    .unwrap_err();
+  assert!(err.to_string().contains("control characters"));
+  assert!(opids
+    .insert_opid_with_path_method("list_users", "/users\n", "get")
+    .is_err());
+  assert_eq!(opids.iter().count(), 0);
+}
+
+#[test]
+fn test_method_case() {
+  let mut opids = OperationIds::default();
+  opids
+    .insert_opid_with_path_method("list_users", "/users", "GET")
+    .unwrap();
+  assert_eq!(
+    opids.opid_for_path_method("/users", "get"),
+    Some("list_users")
+  );
+  assert_eq!(
+    opids.opid_for_path_method("/users", "Get"),
+    Some("list_users")
+  );
+  assert_eq!(
+    opids.path_method_for_opid("list_users"),
+    Some(("/users", "get"))
+  );
+  assert!(opids
+    .insert_synthetic_opid_for_path_method("/users", "get")
+    .is_err());
+  assert_eq!(
+    opids
+      .insert_synthetic_opid_for_path_method("/users", "POST")
+      .unwrap(),
+    "users_post"
+  );
+}
+
+#[test]
+fn test_path_method_display() {
+  let opids = OperationIds::default();
+  let key = |path: &str, method: &str| opids.key(path, method).unwrap().to_string();
+  assert_eq!(key("/users", "get"), "GET /users");
+  assert_eq!(
+    key("/users/{id}/posts/{post_id}", "delete"),
+    "DELETE /users/{id}/posts/{post_id}"
+  );
+}
+
+#[test]
+fn test_decode_paths() {
+  assert_eq!(percent_decode("/files/%7Bname%7D"), "/files/{name}");
+  assert_eq!(percent_decode("/a+b/caf%C3%A9"), "/a b/café");
+  assert_eq!(percent_decode("/100%/%zz/%4"), "/100%/%zz/%4");
+
+  assert_eq!(
+    synthetic(
+      Settings {
+        decode_paths: false,
+        ..Settings::default()
+      },
+      "/files/my%20docs",
+      "get"
+    ),
+    "files_my_20docs_get"
+  );
+  assert_eq!(
+    synthetic(
+      Settings {
+        decode_paths: true,
+        ..Settings::default()
+      },
+      "/files/my%20docs",
+      "get"
+    ),
+    "files_my_docs_get"
+  );
+  assert_eq!(
+    synthetic(
+      Settings {
+        decode_paths: false,
+        ..Settings::default()
+      },
+      "/files/%7Bname%7D",
+      "get"
+    ),
+    "files__7bname_7d_get"
+  );
+  assert_eq!(
+    synthetic(
+      Settings {
+        decode_paths: true,
+        ..Settings::default()
+      },
+      "/files/%7Bname%7D",
+      "get"
+    ),
+    "files__name_get"
+  );
+  assert_eq!(
+    synthetic(
+      Settings {
+        decode_paths: true,
+        ..Settings::default()
+      },
+      "/caf%C3%A9",
+      "get"
+    ),
+    "café_get"
+  );
+
+  // only the name is decoded, the endpoint keeps its path
+  let mut opids = OperationIds::with_settings(Settings {
+    decode_paths: true,
+    ..Settings::default()
+  });
+  opids
+    .insert_synthetic_opid_for_path_method("/files/%7Bname%7D", "get")
+    .unwrap();
+  assert_eq!(
+    opids.path_method_for_opid("files__name_get"),
+    Some(("/files/%7Bname%7D", "get"))
+  );
+}
+
+#[test]
+fn test_method_aliases() {
+  let mut opids = OperationIds::with_settings(Settings {
+    method_aliases: [("get", "fetch"), ("delete", "remove")]
+      .map(|(method, word)| (method.to_string(), word.to_string()))
+      .into(),
+    ..Settings::default()
+  });
+  let mut assign = |path: &str, method: &str| {
+    opids
+      .insert_synthetic_opid_for_path_method(path, method)
+      .unwrap()
+  };
+  assert_eq!(assign("/users", "get"), "users_fetch");
+  assert_eq!(assign("/users/{id}", "delete"), "users_remove_by_id");
+  assert_eq!(assign("/users", "post"), "users_post");
+}
+
+#[test]
+fn test_minimal_suffix() {
+  let endpoints = [
+    ("/users", "get"),
+    ("/users/{id}", "get"),
+    ("/users/{id}", "delete"),
+    ("/users/{name}", "get"),
+  ];
+  let assign = |minimal_suffix: bool| {
+    let mut opids = OperationIds::with_settings(Settings {
+      minimal_suffix,
+      ..Settings::default()
+    });
+    endpoints
+      .iter()
+      .map(|(path, method)| {
+        opids
+          .insert_synthetic_opid_for_path_method(path, method)
+          .unwrap()
+      })
+      .collect::<Vec<_>>()
+  };
+
+  assert_eq!(
+    assign(false),
+    [
+      "users_get",
+      "users_get_by_id",
+      "users_delete_by_id",
+      "users_get_by_name"
+    ]
+  );
+  // the parameters only show up where `users_get` is taken already
+  assert_eq!(
+    assign(true),
+    [
+      "users_get",
+      "users_get_by_id",
+      "users_delete",
+      "users_get_by_name"
+    ]
+  );
+}
+
+#[test]
+fn test_long_id_warning() {
+  let mut opids = OperationIds::with_settings(Settings {
+    warn_id_length: 20,
+    ..Settings::default()
+  });
+  opids
+    .insert_synthetic_opid_for_path_method("/short", "get")
+    .unwrap();
+  assert!(opids.warnings().is_empty());
+
+  let opid = opids
+    .insert_synthetic_opid_for_path_method("/a/rather/long/path", "get")
+    .unwrap();
+  assert_eq!(opid, "a_rather_long_path_get");
+  assert_eq!(
+    opids.warnings(),
+    [Warning::new(
+      "operation id \"a_rather_long_path_get\" of GET /a/rather/long/path is 22 \
+       characters long, more than the recommended 20"
+    )]
+  );
+}
+
+#[test]
+fn test_path_without_alphanumerics_warning() {
+  let mut opids = OperationIds::default();
+  opids
+    .insert_synthetic_opid_for_path_method("/_/-", "get")
+    .unwrap();
+  assert_eq!(opids.warnings().len(), 1);
+  assert!(opids.warnings()[0].message.starts_with("path /_/- of GET"));
+
+  // the root and bare parameters are no typos
+  opids
+    .insert_synthetic_opid_for_path_method("/", "get")
+    .unwrap();
+  opids
+    .insert_synthetic_opid_for_path_method("/{id}", "get")
+    .unwrap();
+  assert_eq!(opids.warnings().len(), 1);
+}
+
+#[test]
+fn test_base_path() {
+  let assign = |server_url: &str, path: &str| {
+    let mut opids = OperationIds::default();
+    opids.set_base_path(server_url).unwrap();
+    opids
+      .insert_synthetic_opid_for_path_method(path, "get")
+      .unwrap()
+  };
+  assert_eq!(
+    assign("https://api.example.com/v2", "/users"),
+    "v2_users_get"
+  );
+  assert_eq!(assign("/api/v1/", "/users/{id}"), "api_v1_users_get_by_id");
+  assert_eq!(assign("https://api.example.com", "/users"), "users_get");
+  assert_eq!(
+    assign("https://api.example.com/2024", "/users"),
+    "n2024_users_get"
+  );
+}
+
+#[test]
+fn test_dump() {
+  let mut opids = OperationIds::default();
+  opids.reserve("users_get").unwrap();
+  opids
+    .insert_opid_with_path_method("list_users", "/users", "get")
+    .unwrap();
+  opids
+    .insert_synthetic_opid_for_path_method("/users/{id}", "get")
+    .unwrap();
+  opids
+    .insert_synthetic_opid_for_path_method("/users", "put")
+    .unwrap();
+
+  assert_eq!(
+    opids.dump(),
+    "operation ids:\n\
+     \x20 list_users -> GET /users (explicit)\n\
+     \x20 users_get -> (reserved)\n\
+     \x20 users_get_by_id -> GET /users/{id} (synthetic, attempt 0)\n\
+     \x20 users_put -> PUT /users (synthetic, attempt 0)\n\
+     endpoints:\n\
+     \x20 GET /users -> list_users\n\
+     \x20 PUT /users -> users_put\n\
+     \x20 GET /users/{id} -> users_get_by_id\n"
+  );
+}
+
+#[test]
+fn test_method_suffix() {
+  let with_policy = |method_suffix: MethodSuffix| {
+    OperationIds::with_settings(Settings {
+      method_suffix,
+      ..Settings::default()
+    })
+  };
+  let alone = OperationInfo::default();
+  let shared = OperationInfo {
+    shares_path: true,
+    ..OperationInfo::default()
+  };
+
+  let mut opids = with_policy(MethodSuffix::Always);
+  let mut assign = |path: &str, method: &str, info: &OperationInfo| {
+    opids
+      .insert_synthetic_opid_for_operation(path, method, info)
+      .unwrap()
+  };
+  assert_eq!(assign("/users", "get", &alone), "users_get");
+  assert_eq!(assign("/users/", "get", &alone), "users1_get");
+
+  let mut opids = with_policy(MethodSuffix::Never);
+  assert_eq!(
+    opids
+      .insert_synthetic_opid_for_operation("/users", "get", &shared)
+      .unwrap(),
+    "users"
+  );
+  let err = opids
+    .insert_synthetic_opid_for_operation("/users", "post", &shared)
+    .unwrap_err();
+  assert!(err
+    .to_string()
+    .contains("\"users\" for POST /users is taken"));
+
+  let mut opids = with_policy(MethodSuffix::Auto);
+  let mut assign = |path: &str, method: &str, info: &OperationInfo| {
+    opids
+      .insert_synthetic_opid_for_operation(path, method, info)
+      .unwrap()
+  };
+  assert_eq!(assign("/status", "get", &alone), "status");
+  assert_eq!(assign("/users", "get", &shared), "users_get");
+  assert_eq!(assign("/users", "post", &shared), "users_post");
+  // a collision still gets a numeric suffix
+  assert_eq!(assign("/status/", "get", &alone), "status1");
+}
+
+#[test]
+fn test_kebab_case() {
+  let mut opids = OperationIds::with_settings(Settings {
+    naming_style: NamingStyle::Kebab,
+    ..Settings::default()
+  });
+  let mut assign = |path: &str, method: &str| {
+    opids
+      .insert_synthetic_opid_for_path_method(path, method)
+      .unwrap()
+  };
+  assert_eq!(assign("/foo/bar", "get"), "foo-bar-get");
+  assert_eq!(assign("/foo_bar", "get"), "foo-bar1-get");
+  assert_eq!(assign("/users/{user_id}", "get"), "users-get-by-user-id");
+  assert_eq!(assign("/2fa/setup", "post"), "n2fa-setup-post");
+}
+
+#[test]
+fn test_spell_out_digits() {
+  assert_eq!(spell_out_digits("v2_items_get"), "v_two_items_get");
+  assert_eq!(spell_out_digits("error404_get"), "error_four_zero_four_get");
+  assert_eq!(spell_out_digits("users_get"), "users_get");
+
+  let mut opids = OperationIds::with_settings(Settings {
+    spell_out_digits: true,
+    ..Settings::default()
+  });
+  let mut assign = |path: &str| {
+    opids
+      .insert_synthetic_opid_for_path_method(path, "get")
+      .unwrap()
+  };
+  assert_eq!(assign("/v2/items"), "v_two_items_get");
+  // collision suffixes are spelled out too
+  assert_eq!(assign("/v2/items/"), "v_two_items_one_get");
+  // and no `n` is needed in front
+  assert_eq!(assign("/2fa"), "two_fa_get");
+}
+
+#[test]
+fn test_sanitize_explicit() {
+  let mut opids = OperationIds::default();
+  let mut insert = |opid: &str, path: &str| {
+    opids
+      .insert_sanitized_opid_with_path_method(opid, path, "get")
+      .unwrap()
+  };
+  assert_eq!(insert("getUsers", "/a"), "get_users");
+  assert_eq!(insert("Create-User", "/b"), "create_user");
+  assert_eq!(insert("get_users", "/c"), "get_users1");
+  assert_eq!(insert("2FA.setup", "/d"), "n2_fa_setup");
+  assert!(opids
+    .insert_sanitized_opid_with_path_method("--", "/e", "get")
+    .is_err());
+  assert_eq!(opids.origin_of("get_users1"), Some(Origin::Explicit));
+}
+
+#[test]
+fn test_method_case_setting() {
+  assert_eq!(
+    synthetic(
+      Settings {
+        method_case: MethodCase::Lower,
+        naming_style: NamingStyle::Snake,
+        ..Settings::default()
+      },
+      "/foo/bar",
+      "get"
+    ),
+    "foo_bar_get"
+  );
+  assert_eq!(
+    synthetic(
+      Settings {
+        method_case: MethodCase::Lower,
+        naming_style: NamingStyle::Camel,
+        ..Settings::default()
+      },
+      "/foo/bar",
+      "get"
+    ),
+    "fooBarGet"
+  );
+  assert_eq!(
+    synthetic(
+      Settings {
+        method_case: MethodCase::Upper,
+        naming_style: NamingStyle::Snake,
+        ..Settings::default()
+      },
+      "/foo/bar",
+      "get"
+    ),
+    "foo_bar_GET"
+  );
+  assert_eq!(
+    synthetic(
+      Settings {
+        method_case: MethodCase::Upper,
+        naming_style: NamingStyle::Camel,
+        ..Settings::default()
+      },
+      "/foo/bar",
+      "get"
+    ),
+    "fooBarGET"
+  );
+  assert_eq!(
+    synthetic(
+      Settings {
+        method_case: MethodCase::Preserve,
+        naming_style: NamingStyle::Kebab,
+        ..Settings::default()
+      },
+      "/foo/bar",
+      "get"
+    ),
+    "foo-bar-get"
+  );
+
+  let aliases: BTreeMap<_, _> = [("post".to_string(), "Create".to_string())].into();
+  assert_eq!(
+    synthetic(
+      Settings {
+        method_case: MethodCase::Lower,
+        method_aliases: aliases.clone(),
+        ..Settings::default()
+      },
+      "/foo",
+      "post"
+    ),
+    "foo_create"
+  );
+  assert_eq!(
+    synthetic(
+      Settings {
+        method_case: MethodCase::Upper,
+        method_aliases: aliases.clone(),
+        ..Settings::default()
+      },
+      "/foo",
+      "post"
+    ),
+    "foo_CREATE"
+  );
+  assert_eq!(
+    synthetic(
+      Settings {
+        method_case: MethodCase::Preserve,
+        method_aliases: aliases.clone(),
+        ..Settings::default()
+      },
+      "/foo",
+      "post"
+    ),
+    "foo_Create"
+  );
+}
+
+#[test]
+fn test_last_segment_only() {
+  let mut opids = OperationIds::with_settings(Settings {
+    last_segment_only: true,
+    ..Settings::default()
+  });
+  let mut assign = |path: &str| {
+    opids
+      .insert_synthetic_opid_for_path_method(path, "get")
+      .unwrap()
+  };
+  assert_eq!(assign("/api/v1/widgets"), "widgets_get");
+  assert_eq!(assign("/api/v1/widgets/{id}"), "widgets_get_by_id");
+  // paths sharing their last segment are told apart by the collision suffix
+  assert_eq!(assign("/a/users"), "users_get");
+  assert_eq!(assign("/b/users"), "users1_get");
+}
+
+#[test]
+fn test_summarize_params() {
+  let mut opids = OperationIds::with_settings(Settings {
+    summarize_params_above: Some(4),
+    ..Settings::default()
+  });
+  let mut assign = |path: &str| {
+    opids
+      .insert_synthetic_opid_for_path_method(path, "get")
+      .unwrap()
+  };
+  assert_eq!(
+    assign("/a/{b}/c/{d}/e/{f}/g/{h}"),
+    "a_c_e_g_get_by_b_by_d_by_f_by_h"
+  );
+  assert_eq!(assign("/a/{b}/{c}/{d}/{e}/{f}"), "a_get_by_5_params");
+  // a colliding summary falls back to listing the parameters
+  assert_eq!(
+    assign("/a/{v}/{w}/{x}/{y}/{z}/"),
+    "a_get_by_v_by_w_by_x_by_y_by_z"
+  );
+  // and that to the collision suffix
+  assert_eq!(
+    assign("/a_/{v}/{w}/{x}/{y}/{z}"),
+    "a1_get_by_v_by_w_by_x_by_y_by_z"
+  );
+}
+
+#[test]
+fn test_intra_segment() {
+  use NamingStyle::{Camel, Snake};
+  assert_eq!(
+    synthetic(
+      Settings {
+        naming_style: Snake,
+        intra_segment: IntraSegment::Split,
+        ..Settings::default()
+      },
+      "/user-profiles/{id}",
+      "get"
+    ),
+    "user_profiles_get_by_id"
+  );
+  assert_eq!(
+    synthetic(
+      Settings {
+        naming_style: Camel,
+        intra_segment: IntraSegment::Split,
+        ..Settings::default()
+      },
+      "/user-profiles/{id}",
+      "get"
+    ),
+    "userProfilesGetById"
+  );
+  assert_eq!(
+    synthetic(
+      Settings {
+        naming_style: Snake,
+        intra_segment: IntraSegment::Join,
+        ..Settings::default()
+      },
+      "/user-profiles/{id}",
+      "get"
+    ),
+    "userprofiles_get_by_id"
+  );
+  assert_eq!(
+    synthetic(
+      Settings {
+        naming_style: Camel,
+        intra_segment: IntraSegment::Join,
+        ..Settings::default()
+      },
+      "/user-profiles/{id}",
+      "get"
+    ),
+    "userprofilesGetById"
+  );
+}
+
+#[test]
+fn test_length_histogram() {
+  let mut opids = OperationIds::default();
+  assert_eq!(opids.length_histogram(), "");
+  opids
+    .insert_opid_with_path_method("a_rather_long_explicit_operation_id", "/x", "get")
+    .unwrap();
+  for path in ["/users", "/groups", "/users/{id}/memberships/{membership}"] {
+    opids
+      .insert_synthetic_opid_for_path_method(path, "get")
+      .unwrap();
+  }
+  assert_eq!(
+    opids.length_histogram(),
+    [
+      "operation id lengths:\n",
+      "    0-9       1 #\n",
+      "   10-19      1 #\n",
+      "   20-29      0\n",
+      "   30-39      0\n",
+      "   40-49      1 #\n",
+    ]
+    .concat()
+  );
+}
+
+#[test]
+fn test_split_words() {
+  let no_acronyms = &[];
+  assert_eq!(split_words("httpProxy", no_acronyms), ["http", "proxy"]);
+  assert_eq!(split_words("getURL", no_acronyms), ["get", "url"]);
+  assert_eq!(split_words("HTTPProxy", no_acronyms), ["http", "proxy"]);
+  assert_eq!(
+    split_words("v2/items_list", no_acronyms),
+    ["v2", "items", "list"]
+  );
+  // without acronyms, capitals running into each other stay together
+  assert_eq!(
+    split_words("XMLHTTPRequest", no_acronyms),
+    ["xmlhttp", "request"]
+  );
+  assert_eq!(split_words("getURLs", no_acronyms), ["get", "ur", "ls"]);
+
+  let acronyms = &["XML".to_string(), "http".to_string(), "URL".to_string()];
+  assert_eq!(
+    split_words("XMLHTTPRequest", acronyms),
+    ["xml", "http", "request"]
+  );
+  assert_eq!(split_words("getURLs", acronyms), ["get", "urls"]);
+  assert_eq!(split_words("APIKey", acronyms), ["api", "key"]);
+}
+
+#[test]
+fn test_camel_case() {
+  let mut opids = OperationIds::with_settings(Settings {
+    naming_style: NamingStyle::Camel,
+    acronyms: vec!["URL".to_string()],
+    ..Settings::default()
+  });
+  assert_eq!(
+    opids
+      .insert_synthetic_opid_for_path_method("/api/httpProxy/getURL", "get")
+      .unwrap(),
+    "apiHttpProxyGetUrlGet"
+  );
+  assert_eq!(
+    opids
+      .insert_synthetic_opid_for_path_method("/users/{userId}", "get")
+      .unwrap(),
+    "usersGetByUserId"
+  );
+
+  let mut opids = OperationIds::with_settings(Settings {
+    naming_style: NamingStyle::Pascal,
+    ..Settings::default()
+  });
+  assert_eq!(
+    opids
+      .insert_synthetic_opid_for_path_method("/XMLHTTPRequest", "post")
+      .unwrap(),
+    "XmlhttpRequestPost"
+  );
+  // collision suffixes stay attached to their word
+  assert_eq!(
+    opids
+      .insert_synthetic_opid_for_path_method("/XMLHTTPRequest/", "post")
+      .unwrap(),
+    "XmlhttpRequest1Post"
+  );
+}
+
+#[test]
+fn test_reserve() {
+  let mut opids = OperationIds::default();
+  opids.reserve("users_get").unwrap();
+  assert!(opids.reserve("users_get").is_err());
+
+  // the reserved name forces a suffix, without showing up as an endpoint
+  assert_eq!(
+    opids
+      .insert_synthetic_opid_for_path_method("/users", "get")
+      .unwrap(),
+    "users1_get"
+  );
+  assert_eq!(opids.path_method_for_opid("users_get"), None);
+  assert_eq!(
+    opids.iter().map(|(opid, _)| opid).collect::<Vec<_>>(),
+    ["users1_get"]
+  );
+  assert_eq!(opids.stats().explicit, 0);
+
+  // it can't be claimed explicitly either
+  assert!(opids
+    .insert_opid_with_path_method("users_get", "/people", "get")
+    .is_err());
+}
+
+#[test]
+fn test_root_name() {
+  assert_eq!(
+    synthetic(
+      Settings {
+        root_name: "root".to_string(),
+        ..Settings::default()
+      },
+      "/",
+      "post"
+    ),
+    "root_post"
+  );
+  assert_eq!(
+    synthetic(
+      Settings {
+        root_name: "index".to_string(),
+        ..Settings::default()
+      },
+      "/",
+      "get"
+    ),
+    "index_get"
+  );
+  assert_eq!(
+    synthetic(
+      Settings {
+        root_name: "index".to_string(),
+        ..Settings::default()
+      },
+      "/{id}",
+      "get"
+    ),
+    "index_get_by_id"
+  );
+  assert_eq!(
+    synthetic(
+      Settings {
+        root_name: "Home Page".to_string(),
+        ..Settings::default()
+      },
+      "/",
+      "get"
+    ),
+    "home_page_get"
+  );
+  // paths with words of their own are unaffected
+  assert_eq!(
+    synthetic(
+      Settings {
+        root_name: "index".to_string(),
+        ..Settings::default()
+      },
+      "/users",
+      "get"
+    ),
+    "users_get"
+  );
+
+  let settings = Settings {
+    method_suffix: MethodSuffix::Never,
+    ..Settings::default()
+  };
+  assert_eq!(synthetic(settings, "/", "get"), "root");
+}
+
+#[test]
+fn test_word_exceptions() {
+  let exceptions: BTreeMap<_, _> = [("oauth", "OAuth"), ("grpc", "gRPC")]
+    .map(|(word, spelling)| (word.to_string(), spelling.to_string()))
+    .into();
+  assert_eq!(
+    synthetic(
+      Settings {
+        naming_style: NamingStyle::Snake,
+        word_exceptions: exceptions.clone(),
+        ..Settings::default()
+      },
+      "/oauth/token",
+      "post"
+    ),
+    "OAuth_token_post"
+  );
+  assert_eq!(
+    synthetic(
+      Settings {
+        naming_style: NamingStyle::Kebab,
+        word_exceptions: exceptions.clone(),
+        ..Settings::default()
+      },
+      "/OAUTH/token",
+      "post"
+    ),
+    "OAuth-token-post"
+  );
+  assert_eq!(
+    synthetic(
+      Settings {
+        naming_style: NamingStyle::Camel,
+        word_exceptions: exceptions.clone(),
+        ..Settings::default()
+      },
+      "/api/grpc/oauth",
+      "post"
+    ),
+    "apiGRPCOAuthPost"
+  );
+  // only whole words are respelled
+  assert_eq!(
+    synthetic(
+      Settings {
+        naming_style: NamingStyle::Snake,
+        word_exceptions: exceptions.clone(),
+        ..Settings::default()
+      },
+      "/oauth2/grpcs",
+      "post"
+    ),
+    "oauth2_grpcs_post"
+  );
+}
+
+#[test]
+fn test_no_separator_at_the_ends() {
+  let styles = [
+    NamingStyle::Snake,
+    NamingStyle::Kebab,
+    NamingStyle::Camel,
+    NamingStyle::Pascal,
+  ];
+  for naming_style in styles {
+    for root_name in ["", "root"] {
+      for alias in ["fetch", "_fetch_", "-fetch-"] {
+        let mut opids = OperationIds::with_settings(Settings {
+          naming_style,
+          root_name: root_name.to_string(),
+          method_aliases: [("get".to_string(), alias.to_string())].into(),
+          ..Settings::default()
+        });
+        for path in ["/", "/{id}", "/_users_/", "/-/items-", "/users"] {
+          let opid = opids
+            .insert_synthetic_opid_for_path_method(path, "get")
+            .unwrap();
+          assert!(!opid.is_empty(), "{path} with {naming_style:?}");
+          if let Some(separator) = naming_style.separator() {
+            assert!(
+              !opid.starts_with(separator) && !opid.ends_with(separator),
+              "{opid:?} for {path} with {naming_style:?} and {alias:?}"
+            );
+          }
+        }
+      }
+    }
+  }
+  let settings = Settings {
+    root_name: String::new(),
+    ..Settings::default()
+  };
+  assert_eq!(synthetic(settings, "/", "get"), "get");
+}
+
+#[test]
+fn test_parent_segment_collisions() {
+  let mut opids = OperationIds::with_settings(Settings {
+    last_segment_only: true,
+    collision_strategy: CollisionStrategy::ParentSegment,
+    ..Settings::default()
+  });
+  let mut assign = |path: &str| {
+    opids
+      .insert_synthetic_opid_for_path_method(path, "get")
+      .unwrap()
+  };
+  assert_eq!(assign("/a/items"), "items_get");
+  assert_eq!(assign("/b/items"), "b_items_get");
+  assert_eq!(assign("/v1/b/items/{id}"), "items_get_by_id");
+  assert_eq!(assign("/v2/b/items"), "v2_b_items_get");
+  // once the segments run out, collisions are numbered
+  assert_eq!(assign("/b-items"), "b_items1_get");
+  assert_eq!(assign("/"), "root_get");
+  assert_eq!(assign("/{id}"), "root_get_by_id");
+  assert_eq!(assign("/_"), "root1_get");
+}
+
+#[test]
+fn test_casing_mix() {
+  assert_eq!(casing_of("get_users"), "snake_case");
+  assert_eq!(casing_of("GET_USERS"), "SCREAMING_SNAKE_CASE");
+  assert_eq!(casing_of("get-users"), "kebab-case");
+  assert_eq!(casing_of("users"), "lowercase");
+  assert_eq!(casing_of("getUsers2"), "camelCase");
+  assert_eq!(casing_of("GetUsers"), "PascalCase");
+  assert_eq!(casing_of("get_Users"), "mixed");
+
+  let mut opids = OperationIds::default();
+  assert_eq!(opids.casing_mix(), "");
+  for (opid, path) in [
+    ("getUsers", "/users"),
+    ("createUser", "/users/new"),
+    ("delete_user", "/users/delete"),
+  ] {
+    opids
+      .insert_opid_with_path_method(opid, path, "post")
+      .unwrap();
+  }
+  opids
+    .insert_synthetic_opid_for_path_method("/groups", "get")
+    .unwrap();
+  assert_eq!(
+    opids.casing_mix(),
+    "explicit operation ids use 2 casing styles:\n  \
+     camelCase: 2, like createUser\n  \
+     snake_case: 1, like delete_user\n"
+  );
+}
+
+#[test]
+fn test_tag_namespace() {
+  let assign = |joiner: &str, naming_style, tag: Option<&str>, path: &str| {
+    OperationIds::with_settings(Settings {
+      tag_namespace: Some(joiner.to_string()),
+      naming_style,
+      ..Settings::default()
+    })
+    .insert_synthetic_opid_for_operation(
+      path,
+      "get",
+      &OperationInfo {
+        tag: tag.map(String::from),
+        ..OperationInfo::default()
+      },
+    )
+    .unwrap()
+  };
+  let opid = assign(".", NamingStyle::Snake, Some("Billing"), "/invoices");
+  assert_eq!(opid, "billing.invoices_get");
+  assert_eq!(
+    split_namespace(&opid, "."),
+    (Some("billing"), "invoices_get")
+  );
+  assert_eq!(
+    assign(
+      "::",
+      NamingStyle::Snake,
+      Some("user accounts"),
+      "/v1.2/users"
+    ),
+    "user_accounts::v1_2_users_get"
+  );
+  assert_eq!(
+    assign(".", NamingStyle::Pascal, Some("billing"), "/invoices"),
+    "Billing.InvoicesGet"
+  );
+
+  // untagged operations stay outside of any namespace
+  let opid = assign(".", NamingStyle::Snake, None, "/invoices");
+  assert_eq!(opid, "invoices_get");
+  assert_eq!(split_namespace(&opid, "."), (None, "invoices_get"));
+  assert_eq!(assign(".", NamingStyle::Snake, Some("--"), "/x"), "x_get");
+}
+
+#[test]
+fn test_catch_all() {
+  assert_eq!(catch_all_params("/{id}/api/{proxy+}"), ["proxy"]);
+  assert_eq!(catch_all_params("/{+}/{id}"), Vec::<&str>::new());
+
+  assert_eq!(
+    synthetic(
+      Settings {
+        mark_catch_all: false,
+        ..Settings::default()
+      },
+      "/{proxy+}",
+      "get"
+    ),
+    "root_get_by_proxy"
+  );
+  assert_eq!(
+    synthetic(
+      Settings {
+        mark_catch_all: true,
+        ..Settings::default()
+      },
+      "/{proxy+}",
+      "get"
+    ),
+    "root_get_by_proxy_catchall"
+  );
+  assert_eq!(
+    synthetic(
+      Settings {
+        mark_catch_all: true,
+        ..Settings::default()
+      },
+      "/users/{id}/{proxy+}",
+      "get"
+    ),
+    "users_get_by_id_by_proxy_catchall"
+  );
+  assert_eq!(
+    synthetic(
+      Settings {
+        mark_catch_all: true,
+        ..Settings::default()
+      },
+      "/users/{id}",
+      "get"
+    ),
+    "users_get_by_id"
+  );
+}
+
+#[test]
+fn test_dedupe_params() {
+  assert_eq!(
+    synthetic(
+      Settings {
+        dedupe_params: false,
+        ..Settings::default()
+      },
+      "/{id}/sub/{id}/{name}",
+      "get"
+    ),
+    "sub_get_by_id_by_id_by_name"
+  );
+  assert_eq!(
+    synthetic(
+      Settings {
+        dedupe_params: true,
+        ..Settings::default()
+      },
+      "/{id}/sub/{id}/{name}",
+      "get"
+    ),
+    "sub_get_by_id_by_name"
+  );
+
+  // the key still knows about every parameter
+  let mut opids = OperationIds::with_settings(Settings {
+    dedupe_params: true,
+    ..Settings::default()
+  });
+  let opid = opids
+    .insert_synthetic_opid_for_path_method("/{id}/sub/{id}/{name}", "get")
+    .unwrap();
+  assert_eq!(
+    opids.path_method(&opid).unwrap().params(),
+    ["id", "id", "name"]
+  );
+}
+
+#[test]
+fn test_strip_extension() {
+  assert_eq!(strip_extension("/report.pdf"), "/report");
+  assert_eq!(
+    strip_extension("/files/{}/data.tar.gz"),
+    "/files/{}/data.tar"
+  );
+  assert_eq!(strip_extension("/.well-known"), "/.well-known");
+  assert_eq!(strip_extension("/v1.2/users"), "/v1.2/users");
+  assert_eq!(strip_extension("/files/{}"), "/files/{}");
+
+  let mut opids = OperationIds::with_settings(Settings {
+    strip_extension: true,
+    ..Settings::default()
+  });
+  assert_eq!(
+    opids
+      .insert_synthetic_opid_for_path_method("/report.pdf", "get")
+      .unwrap(),
+    "report_get"
+  );
+  // the extension is only dropped for naming, so the endpoints stay apart
+  assert_eq!(
+    opids
+      .insert_synthetic_opid_for_path_method("/report.csv", "get")
+      .unwrap(),
+    "report1_get"
+  );
+  assert_eq!(
+    opids.opid_for_path_method("/report.pdf", "get"),
+    Some("report_get")
+  );
+}
+
+#[test]
+fn test_method_prefix_layout() {
+  let mut opids = OperationIds::with_settings(Settings {
+    layout: Layout::MethodPrefix,
+    ..Settings::default()
+  });
+  let mut assign = |path, method| {
+    opids
+      .insert_synthetic_opid_for_path_method(path, method)
+      .unwrap()
+  };
+  assert_eq!(assign("/foo/bar", "get"), "GET__foo_bar");
+  assert_eq!(assign("/foo/bar", "post"), "POST__foo_bar");
+  // collisions and the guard against leading digits work on the path
+  assert_eq!(assign("/foo_bar", "get"), "GET__foo_bar1");
+  assert_eq!(assign("/2fa", "get"), "GET__n2fa");
+
+  let settings = Settings {
+    layout: Layout::MethodPrefix,
+    method_separator: "-".to_string(),
+    naming_style: NamingStyle::Camel,
+    ..Settings::default()
+  };
+  assert_eq!(synthetic(settings, "/foo/{id}", "delete"), "DELETE-fooById");
+}
+
+#[test]
+fn test_normalize_unicode() {
+  let composed = "/caf\u{e9}/{id}";
+  let decomposed = "/cafe\u{301}/{id}";
+
+  let mut opids = OperationIds::default();
+  opids
+    .insert_synthetic_opid_for_path_method(composed, "get")
+    .unwrap();
+  assert!(opids
+    .insert_synthetic_opid_for_path_method(decomposed, "get")
+    .is_ok());
+
+  let mut opids = OperationIds::with_settings(Settings {
+    normalize_unicode: true,
+    ..Settings::default()
+  });
+  let opid = opids
+    .insert_synthetic_opid_for_path_method(decomposed, "get")
+    .unwrap();
+  assert_eq!(
+    opids.opid_for_path_method(composed, "get"),
+    Some(opid.as_str())
+  );
+  let err = opids
+    .insert_opid_with_path_method("cafe_get", composed, "get")
+    .unwrap_err();
+  assert!(err.to_string().contains("is already present"), "{err}");
+}
@@ -0,0 +1,213 @@
+use anyhow::{anyhow, Result};
+use openapiv3::OpenAPI;
+
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::Path;
+
+/// Format of a spec read from stdin or written to stdout, where there is no
+/// file name to tell
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum Format {
+  #[default]
+  Json,
+  Yaml,
+}
+
+/// Whether `path` is `-`, standing for stdout
+pub fn is_stdout(path: &Path) -> bool {
+  path.as_os_str() == "-"
+}
+
+/// The format to write the output at `path` in: `stdout_format` for stdout,
+/// YAML for files ending in `.yaml` or `.yml`, and JSON otherwise
+pub fn output_format(path: &Path, stdout_format: Format) -> Format {
+  if is_stdout(path) {
+    return stdout_format;
+  }
+  crate::input::extension_format(path).unwrap_or_default()
+}
+
+/// Check that the output file can be written before doing the work for
+/// it. Unless `overwrite` is set, an existing file is an error rather than
+/// being replaced.
+pub fn check_output(path: &Path, overwrite: bool) -> Result<()> {
+  if !overwrite && !is_stdout(path) && path.exists() {
+    return Err(anyhow!(
+      "{} exists already, pass --overwrite to replace it",
+      path.display()
+    ));
+  }
+  Ok(())
+}
+
+/// Write the output with `write`, to stdout if `path` is `-`, or to the
+/// output file otherwise, see [`check_output`]. The file is written to a
+/// temporary file next to it first, which replaces it once `write`
+/// succeeded, so an interrupted or failed write never leaves a truncated
+/// output file behind.
+pub fn write_output(
+  path: &Path,
+  overwrite: bool,
+  write: impl FnOnce(&mut dyn Write) -> Result<()>,
+) -> Result<()> {
+  if is_stdout(path) {
+    return write(&mut std::io::stdout().lock());
+  }
+  check_output(path, overwrite)?;
+  let file_name = path
+    .file_name()
+    .ok_or_else(|| anyhow!("{} is no file path", path.display()))?;
+  let temp = path.with_file_name(format!(
+    ".{}.{}.tmp",
+    file_name.to_string_lossy(),
+    std::process::id()
+  ));
+  // no live process writes a temporary file named after this process, so
+  // one that exists is left over from a crashed run that had the same pid
+  let _ = std::fs::remove_file(&temp);
+  let result = (|| -> Result<()> {
+    let mut writer = BufWriter::new(File::create_new(&temp)?);
+    write(&mut writer)?;
+    writer
+      .into_inner()
+      .map_err(|err| err.into_error())?
+      .sync_all()?;
+    // the file may have shown up while writing
+    check_output(path, overwrite)?;
+    std::fs::rename(&temp, path)?;
+    Ok(())
+  })();
+  if result.is_err() {
+    let _ = std::fs::remove_file(&temp);
+  }
+  result
+}
+
+/// Serialize a spec as JSON, pretty printed for review unless `compact` is
+/// set
+pub fn write_spec(writer: impl Write, spec: &OpenAPI, compact: bool) -> Result<()> {
+  if compact {
+    serde_json::to_writer(writer, spec)?;
+  } else {
+    serde_json::to_writer_pretty(writer, spec)?;
+  }
+  Ok(())
+}
+
+/// Serialize a spec in `format`. YAML is always written block style,
+/// `compact` only applies to JSON.
+pub fn write_spec_as(
+  writer: impl Write,
+  spec: &OpenAPI,
+  format: Format,
+  compact: bool,
+) -> Result<()> {
+  match format {
+    Format::Json => write_spec(writer, spec, compact),
+    Format::Yaml => Ok(serde_yaml::to_writer(writer, spec)?),
+  }
+}
+
+#[test]
+fn test_output_format() {
+  assert_eq!(output_format(Path::new("-"), Format::Yaml), Format::Yaml);
+  assert_eq!(
+    output_format(Path::new("out.json"), Format::Yaml),
+    Format::Json
+  );
+  assert_eq!(
+    output_format(Path::new("out.yaml"), Format::Json),
+    Format::Yaml
+  );
+  assert_eq!(
+    output_format(Path::new("out.YML"), Format::Json),
+    Format::Yaml
+  );
+  assert_eq!(output_format(Path::new("out"), Format::Yaml), Format::Json);
+}
+
+#[test]
+fn test_compact_matches_pretty() {
+  let mut spec = crate::spec::mk_spec(
+    r#"{
+      "/users": { "get": { "responses": {} } },
+      "/users/{id}": { "get": { "operationId": "get_user", "responses": {} } }
+    }"#,
+  );
+  crate::generate::gen_operation_ids(&mut spec, &Default::default()).unwrap();
+
+  let mut pretty = Vec::new();
+  let mut compact = Vec::new();
+  write_spec(&mut pretty, &spec, false).unwrap();
+  write_spec(&mut compact, &spec, true).unwrap();
+
+  assert!(compact.len() < pretty.len());
+  assert!(!compact.contains(&b'\n'));
+  let pretty: serde_json::Value = serde_json::from_slice(&pretty).unwrap();
+  let compact: serde_json::Value = serde_json::from_slice(&compact).unwrap();
+  assert_eq!(pretty, compact);
+  assert_eq!(
+    compact["paths"]["/users"]["get"]["operationId"],
+    "users_get"
+  );
+}
+
+#[test]
+fn test_json_stdin_to_yaml_stdout() {
+  let stdin = r#"{
+    "openapi": "3.0.1",
+    "info": { "title": "test", "version": "1" },
+    "paths": { "/users": { "get": { "responses": {} } } }
+  }"#;
+  let mut spec =
+    crate::input::read_stream(stdin.as_bytes(), Some(Format::Json), false, "stdin")
+      .unwrap();
+  crate::generate::gen_operation_ids(&mut spec, &Default::default()).unwrap();
+
+  let mut stdout = Vec::new();
+  write_spec_as(&mut stdout, &spec, Format::Yaml, false).unwrap();
+  let yaml = String::from_utf8(stdout).unwrap();
+  assert!(yaml.contains("operationId: users_get"), "{yaml}");
+
+  let read_back =
+    crate::input::read_stream(yaml.as_bytes(), Some(Format::Yaml), false, "stdin")
+      .unwrap();
+  assert_eq!(
+    serde_json::to_value(&read_back).unwrap(),
+    serde_json::to_value(&spec).unwrap()
+  );
+}
+
+#[test]
+fn test_write_output_atomically() {
+  let dir = std::env::temp_dir().join(format!("verge-output-{}", std::process::id()));
+  let _ = std::fs::remove_dir_all(&dir);
+  std::fs::create_dir_all(&dir).unwrap();
+  let path = dir.join("out.json");
+  std::fs::write(&path, "original").unwrap();
+
+  let err = write_output(&path, true, |writer| {
+    writer.write_all(b"partial")?;
+    Err(anyhow!("serializing failed"))
+  })
+  .unwrap_err();
+  assert_eq!(err.to_string(), "serializing failed");
+  assert_eq!(std::fs::read_to_string(&path).unwrap(), "original");
+  // no temporary file is left behind
+  assert_eq!(std::fs::read_dir(&dir).unwrap().count(), 1);
+
+  assert!(write_output(&path, false, |writer| Ok(writer.write_all(b"new")?)).is_err());
+  assert_eq!(std::fs::read_to_string(&path).unwrap(), "original");
+  write_output(&path, true, |writer| Ok(writer.write_all(b"new")?)).unwrap();
+  assert_eq!(std::fs::read_to_string(&path).unwrap(), "new");
+
+  // a stale temporary file of an earlier run does not get in the way
+  let stale = dir.join(format!(".out.json.{}.tmp", std::process::id()));
+  std::fs::write(&stale, "stale").unwrap();
+  write_output(&path, true, |writer| Ok(writer.write_all(b"newer")?)).unwrap();
+  assert_eq!(std::fs::read_to_string(&path).unwrap(), "newer");
+  assert!(!stale.exists());
+
+  std::fs::remove_dir_all(&dir).unwrap();
+}
@@ -0,0 +1,76 @@
+//! A smoke test of the installed generator that needs no spec file
+
+use anyhow::{anyhow, Result};
+
+use crate::generate::gen_operation_ids;
+use crate::input::parse_spec;
+use crate::settings::Settings;
+
+/// Spec covering the basics: explicit and synthetic operation IDs, path
+/// parameters, and a collision
+const SPEC: &str = r#"{
+  "openapi": "3.0.1",
+  "info": { "title": "selftest", "version": "1" },
+  "paths": {
+    "/users": {
+      "get": { "operationId": "list_users", "responses": {} },
+      "post": { "responses": {} }
+    },
+    "/users/{id}": { "delete": { "responses": {} } },
+    "/users_": { "post": { "responses": {} } }
+  }
+}"#;
+
+/// The operation IDs [`SPEC`] must get with the default settings
+const EXPECTED: [(&str, &str, &str); 4] = [
+  ("/users", "get", "list_users"),
+  ("/users", "post", "users_post"),
+  ("/users/{id}", "delete", "users_delete_by_id"),
+  ("/users_", "post", "users1_post"),
+];
+
+/// Assign operation IDs to [`SPEC`] and compare them to [`EXPECTED`]
+fn check() -> Result<()> {
+  let mut spec = parse_spec(SPEC, false)?;
+  let opids = gen_operation_ids(&mut spec, &Settings::default())?;
+  let mismatches = EXPECTED
+    .iter()
+    .filter_map(|(path, method, expected)| {
+      let actual = opids.opid_for_path_method(path, method);
+      (actual != Some(*expected)).then(|| {
+        format!(
+          "{} {path}: expected {expected:?}, got {actual:?}",
+          method.to_uppercase()
+        )
+      })
+    })
+    .collect::<Vec<_>>();
+  if mismatches.is_empty() {
+    Ok(())
+  } else {
+    Err(anyhow!(
+      "unexpected operation ids:\n  {}",
+      mismatches.join("\n  ")
+    ))
+  }
+}
+
+/// Run the self test, printing `PASS` or `FAIL`. Fails if the generated
+/// operation IDs are not the expected ones.
+pub fn run() -> Result<()> {
+  match check() {
+    Ok(()) => {
+      println!("PASS");
+      Ok(())
+    }
+    Err(err) => {
+      println!("FAIL");
+      Err(err)
+    }
+  }
+}
+
+#[test]
+fn test_selftest() {
+  check().unwrap();
+}
@@ -0,0 +1,361 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use std::collections::{BTreeMap, BTreeSet};
+
+/// Method names progenitor puts on the generated client itself. Operations
+/// named like this would shadow them.
+pub const DEFAULT_RESERVED_NAMES: [&str; 7] = [
+  "api_version",
+  "baseurl",
+  "build",
+  "client",
+  "new",
+  "new_with_client",
+  "send",
+];
+
+/// When synthetic operation IDs end in the method
+#[derive(
+  Clone,
+  Copy,
+  Debug,
+  Default,
+  PartialEq,
+  Eq,
+  clap::ValueEnum,
+  Serialize,
+  Deserialize,
+  JsonSchema,
+)]
+#[serde(rename_all = "kebab-case")]
+pub enum MethodSuffix {
+  /// Always append the method, like `users_get`. Collisions get a numeric
+  /// suffix.
+  #[default]
+  Always,
+  /// Never append the method, like `users`. Since the method can then not
+  /// tell operations apart, a collision is an error.
+  Never,
+  /// Only append the method when other operations share the path.
+  /// Collisions get a numeric suffix.
+  Auto,
+}
+
+/// Where the method goes in synthetic operation IDs
+#[derive(
+  Clone,
+  Copy,
+  Debug,
+  Default,
+  PartialEq,
+  Eq,
+  clap::ValueEnum,
+  Serialize,
+  Deserialize,
+  JsonSchema,
+)]
+#[serde(rename_all = "kebab-case")]
+pub enum Layout {
+  /// The words of the path, then the method as [`Settings::method_suffix`]
+  /// says, like `foo_bar_get`
+  #[default]
+  PathFirst,
+  /// The uppercase method in front, set apart from the words of the path by
+  /// [`Settings::method_separator`], like `GET__foo_bar`. The naming style
+  /// only applies to the words of the path.
+  MethodPrefix,
+}
+
+/// How synthetic operation IDs that would collide are told apart
+#[derive(
+  Clone,
+  Copy,
+  Debug,
+  Default,
+  PartialEq,
+  Eq,
+  clap::ValueEnum,
+  Serialize,
+  Deserialize,
+  JsonSchema,
+)]
+#[serde(rename_all = "kebab-case")]
+pub enum CollisionStrategy {
+  /// Append a number, like `users1_get`
+  #[default]
+  Number,
+  /// With [`Settings::last_segment_only`], prepend the parent segments of
+  /// the path one by one, like `b_users_get` for `/b/users`, and only
+  /// append a number once the path runs out of segments. Without it, the
+  /// whole path is in the operation ID already, so this is like
+  /// [`CollisionStrategy::Number`].
+  ParentSegment,
+}
+
+/// The case of the method in synthetic operation IDs
+#[derive(
+  Clone,
+  Copy,
+  Debug,
+  Default,
+  PartialEq,
+  Eq,
+  clap::ValueEnum,
+  Serialize,
+  Deserialize,
+  JsonSchema,
+)]
+#[serde(rename_all = "kebab-case")]
+pub enum MethodCase {
+  /// `users_get`, method aliases are lowercased too
+  #[default]
+  Lower,
+  /// `users_GET` or `usersGET`, method aliases are uppercased too
+  Upper,
+  /// The lowercase method, or the method alias as it is spelled
+  Preserve,
+}
+
+/// How the words of synthetic operation IDs are joined
+#[derive(
+  Clone,
+  Copy,
+  Debug,
+  Default,
+  PartialEq,
+  Eq,
+  clap::ValueEnum,
+  Serialize,
+  Deserialize,
+  JsonSchema,
+)]
+#[serde(rename_all = "kebab-case")]
+pub enum NamingStyle {
+  /// `foo_bar_get`, what progenitor expects
+  #[default]
+  Snake,
+  /// `foo-bar-get`, for generators of other languages
+  Kebab,
+  /// `fooBarGet`. Words are also split where the case of the path changes,
+  /// see [`Settings::acronyms`].
+  Camel,
+  /// `FooBarGet`, like [`NamingStyle::Camel`] with the first word
+  /// capitalized too
+  Pascal,
+}
+
+impl NamingStyle {
+  /// The character joining the words, if any
+  pub fn separator(self) -> Option<char> {
+    match self {
+      NamingStyle::Snake => Some('_'),
+      NamingStyle::Kebab => Some('-'),
+      NamingStyle::Camel | NamingStyle::Pascal => None,
+    }
+  }
+}
+
+/// What punctuation within a path segment, like the `-` of
+/// `/user-profiles`, does to synthetic operation IDs
+#[derive(
+  Clone,
+  Copy,
+  Debug,
+  Default,
+  PartialEq,
+  Eq,
+  clap::ValueEnum,
+  Serialize,
+  Deserialize,
+  JsonSchema,
+)]
+#[serde(rename_all = "kebab-case")]
+pub enum IntraSegment {
+  /// Separate words like slashes do, `user_profiles` or `userProfiles`
+  #[default]
+  Split,
+  /// Drop it, so the segment stays a single word, `userprofiles`
+  Join,
+}
+
+/// Settings controlling how operation IDs are assigned. The defaults
+/// reproduce the behavior of the generator without any options.
+#[derive(Clone, Debug, Serialize)]
+pub struct Settings {
+  /// Treat paths that only differ in letter case as the same endpoint, for
+  /// servers that route case-insensitively. Such endpoints share a single
+  /// operation ID.
+  pub case_insensitive_paths: bool,
+  /// Bring paths into Unicode normalization form C before using them as
+  /// keys, so paths that look the same but compose their characters
+  /// differently, like `é` and `e` with a combining accent, are the same
+  /// endpoint. They are reported as duplicates then.
+  pub normalize_unicode: bool,
+  /// Names synthetic operation IDs never use, because they would shadow
+  /// helpers of the generated client. Explicit operation IDs using one of
+  /// them are warned about.
+  pub reserved_names: BTreeSet<String>,
+  /// Operation IDs that are kept free for methods added to the client by
+  /// hand. Synthetic operation IDs skip them and explicit ones must not
+  /// use them.
+  pub reserved_operation_ids: Vec<String>,
+  /// Append the path parameters to synthetic operation IDs, like
+  /// `users_get_by_id` for `GET /users/{id}`
+  pub params_in_id: bool,
+  /// Only append the path parameters to a synthetic operation ID when the
+  /// name without them is taken already, like `users_get` for
+  /// `GET /users/{id}` unless `GET /users` exists too
+  pub minimal_suffix: bool,
+  /// Synthetic operation IDs of paths with more parameters than this only
+  /// count them, like `users_get_by_6_params`, instead of listing every
+  /// one. They are still listed if the summary is taken already.
+  pub summarize_params_above: Option<usize>,
+  /// Lowercase methods whose operations get no operation ID, as if the
+  /// spec did not have them, like `options` and `head`. Their explicit
+  /// operation IDs do not take part in collisions either.
+  pub skip_methods: BTreeSet<String>,
+  /// Tags whose operations must carry an explicit operation ID, like
+  /// `public` for endpoints whose names have to stay stable. Assigning
+  /// operation IDs fails for those that lack one.
+  pub deny_synthetic_tags: BTreeSet<String>,
+  /// Template synthetic operation IDs are rendered from, instead of the
+  /// default `path_method_params` naming. See
+  /// [`crate::opid::TEMPLATE_PLACEHOLDERS`] for what it may contain.
+  pub template: Option<String>,
+  /// Only name synthetic operation IDs after the last segment of the path
+  /// that is not a parameter, like `widgets_get` for `/api/v1/widgets`, for
+  /// flat clients. Paths sharing it get collision suffixes.
+  pub last_segment_only: bool,
+  /// Put synthetic operation IDs of tagged operations into a namespace
+  /// named after their first tag, joined by this, like `billing.invoices_get`
+  /// for `.`. See [`crate::opid::split_namespace`].
+  pub tag_namespace: Option<String>,
+  /// Mark path parameters catching all the rest of the path, like
+  /// `{proxy+}`, in synthetic operation IDs, like `_by_proxy_catchall`
+  pub mark_catch_all: bool,
+  /// Spell out path parameters repeated by mistake only once in synthetic
+  /// operation IDs, like `sub_get_by_id` for `/{id}/sub/{id}`
+  pub dedupe_params: bool,
+  /// Append a word for the content type of the request body to synthetic
+  /// operation IDs, like `files_post_multipart`, see
+  /// [`crate::strategy::ByRequestContentType`]
+  pub request_content_type: bool,
+  /// Name operations lacking an operation ID as the
+  /// [`crate::spec::OPERATION_NAMES_EXTENSION`] of the spec's `info` says,
+  /// where it has an entry for them
+  pub info_operation_names: bool,
+  /// How synthetic operation IDs that would collide are told apart
+  pub collision_strategy: CollisionStrategy,
+  /// Where the method goes in synthetic operation IDs
+  pub layout: Layout,
+  /// What sets the method apart from the rest with [`Layout::MethodPrefix`]
+  pub method_separator: String,
+  /// The word synthetic operation IDs use for paths without any words of
+  /// their own, like the root, so `POST /` becomes `root_post` rather than
+  /// `_post`
+  pub root_name: String,
+  /// Percent-decode paths before deriving synthetic operation IDs from
+  /// them, so `/files/%7Bname%7D` is named like `/files/{name}`. The
+  /// paths themselves are left alone.
+  pub decode_paths: bool,
+  /// Drop a file extension from the last segment of paths before deriving
+  /// synthetic operation IDs from them, so `/report.pdf` is named like
+  /// `/report`. Paths differing only in their extension collide then.
+  pub strip_extension: bool,
+  /// Prepend the path of the first server URL of the spec to synthetic
+  /// operation IDs, so specs differing only in their base path do not
+  /// collide when they are merged later
+  pub base_path_in_id: bool,
+  /// Synthetic operation IDs longer than this are warned about, since they
+  /// make for unwieldy method names. They are still used as they are.
+  pub warn_id_length: usize,
+  /// Words to use for methods in synthetic operation IDs, keyed by the
+  /// lowercase method, like `get` → `fetch`. Methods without an alias
+  /// appear as they are.
+  pub method_aliases: BTreeMap<String, String>,
+  /// Spellings of words that lowercasing would ruin, keyed by the lowercase
+  /// word, like `oauth` → `OAuth` for `OAuth_token`. They apply to whole
+  /// words of operation IDs only.
+  pub word_exceptions: BTreeMap<String, String>,
+  /// When the method is appended to synthetic operation IDs
+  pub method_suffix: MethodSuffix,
+  /// The case of the method in synthetic operation IDs, which the naming
+  /// style does not change
+  pub method_case: MethodCase,
+  /// How the words of synthetic operation IDs are joined
+  pub naming_style: NamingStyle,
+  /// What punctuation within a path segment does
+  pub intra_segment: IntraSegment,
+  /// Acronyms like `URL` or `HTTP` that are words of their own where they
+  /// run into other capitals, so `/XMLHTTPRequest` becomes
+  /// `xmlHttpRequestGet` rather than `xmlhttpRequestGet`. Only used by
+  /// [`NamingStyle::Camel`] and [`NamingStyle::Pascal`].
+  pub acronyms: Vec<String>,
+  /// Spell out the digits of synthetic operation IDs, like `v_two_items_get`
+  /// for `/v2/items`, for tools that dislike digits next to separators
+  pub spell_out_digits: bool,
+  /// Bring explicit operation IDs in line with synthetic ones, like
+  /// `get_users` for `getUsers`, and resolve the collisions this causes
+  /// with suffixes. The spec gets the sanitized operation IDs.
+  pub sanitize_explicit: bool,
+  /// Write the assigned operation IDs into the operations of the spec.
+  /// Without it, the spec is left untouched and the operation IDs are only
+  /// available from the returned [`crate::opid::OperationIds`], for example
+  /// to write a mapping file.
+  pub write_back: bool,
+  /// Stamp every operation written back with an `x-operation-origin`
+  /// extension, `explicit` or `synthetic`, so consumers can tell which
+  /// method names the spec guarantees
+  pub annotate_origin: bool,
+  /// Inline path items that are only a `$ref` into the spec itself before
+  /// assigning operation IDs, instead of skipping them
+  pub resolve_refs: bool,
+  /// Keep going when an operation ID cannot be assigned, and report all
+  /// such errors together at the end instead of just the first one
+  pub collect_errors: bool,
+}
+
+impl Default for Settings {
+  fn default() -> Self {
+    Self {
+      case_insensitive_paths: false,
+      normalize_unicode: false,
+      reserved_names: DEFAULT_RESERVED_NAMES.map(String::from).into(),
+      reserved_operation_ids: Vec::new(),
+      params_in_id: true,
+      minimal_suffix: false,
+      summarize_params_above: None,
+      skip_methods: BTreeSet::new(),
+      deny_synthetic_tags: BTreeSet::new(),
+      template: None,
+      last_segment_only: false,
+      collision_strategy: CollisionStrategy::default(),
+      layout: Layout::default(),
+      method_separator: "__".to_string(),
+      tag_namespace: None,
+      mark_catch_all: false,
+      dedupe_params: false,
+      request_content_type: false,
+      info_operation_names: false,
+      root_name: "root".to_string(),
+      decode_paths: false,
+      strip_extension: false,
+      base_path_in_id: false,
+      warn_id_length: 64,
+      method_aliases: BTreeMap::new(),
+      word_exceptions: BTreeMap::new(),
+      method_suffix: MethodSuffix::default(),
+      method_case: MethodCase::default(),
+      naming_style: NamingStyle::default(),
+      intra_segment: IntraSegment::default(),
+      acronyms: Vec::new(),
+      spell_out_digits: false,
+      sanitize_explicit: false,
+      write_back: true,
+      annotate_origin: false,
+      resolve_refs: false,
+      collect_errors: false,
+    }
+  }
+}
@@ -0,0 +1,192 @@
+//! Small helpers for poking at [`openapiv3`] documents that the crate does
+//! not provide itself.
+
+use anyhow::{anyhow, Result};
+use openapiv3::{OpenAPI, Operation, PathItem};
+
+use std::collections::{BTreeMap, BTreeSet};
+
+/// HTTP methods a path item can carry operations for, in the order
+/// [`PathItem::iter`] yields them.
+pub const METHODS: [&str; 8] = [
+  "get", "put", "post", "delete", "options", "head", "patch", "trace",
+];
+
+/// Get the slot holding the operation for `method` on a path item, so it
+/// can be filled or cleared. Returns [`None`] for unknown methods.
+pub fn operation_slot<'a>(
+  item: &'a mut PathItem,
+  method: &str,
+) -> Option<&'a mut Option<Operation>> {
+  match method {
+    "get" => Some(&mut item.get),
+    "put" => Some(&mut item.put),
+    "post" => Some(&mut item.post),
+    "delete" => Some(&mut item.delete),
+    "options" => Some(&mut item.options),
+    "head" => Some(&mut item.head),
+    "patch" => Some(&mut item.patch),
+    "trace" => Some(&mut item.trace),
+    _ => None,
+  }
+}
+
+/// Remove the operations for `methods` from every path item. Returns how
+/// many operations were removed.
+pub fn remove_methods(spec: &mut OpenAPI, methods: &BTreeSet<String>) -> usize {
+  let mut removed = 0;
+  for item in spec.paths.paths.values_mut() {
+    let Some(item) = item.as_item_mut() else {
+      continue;
+    };
+    for method in methods {
+      if let Some(slot) = operation_slot(item, method)
+        && slot.take().is_some()
+      {
+        removed += 1;
+      }
+    }
+  }
+  removed
+}
+
+/// Extension marking operations as internal, which `--exclude-internal`
+/// keeps out of the generated client
+pub const INTERNAL_EXTENSION: &str = "x-internal";
+
+/// Whether `op` is marked internal by [`INTERNAL_EXTENSION`], as either
+/// `true` or `"true"`
+pub fn is_internal(op: &Operation) -> bool {
+  match op.extensions.get(INTERNAL_EXTENSION) {
+    Some(serde_json::Value::Bool(internal)) => *internal,
+    Some(serde_json::Value::String(internal)) => internal.eq_ignore_ascii_case("true"),
+    _ => false,
+  }
+}
+
+/// Remove the operations marked internal, see [`is_internal`], along with
+/// the path items left without operations by that. Returns how many
+/// operations were removed.
+pub fn remove_internal(spec: &mut OpenAPI) -> usize {
+  let mut removed = 0;
+  spec.paths.paths.retain(|_, item| {
+    let Some(item) = item.as_item_mut() else {
+      return true;
+    };
+    let mut emptied = false;
+    for method in METHODS {
+      if let Some(slot) = operation_slot(item, method)
+        && slot.as_ref().is_some_and(is_internal)
+      {
+        *slot = None;
+        removed += 1;
+        emptied = true;
+      }
+    }
+    !emptied || item.iter().next().is_some()
+  });
+  removed
+}
+
+/// Root extension listing the paths whose `null` path items were removed
+/// while reading the spec, see [`crate::validate::skip_null_path_items`].
+/// It never makes it into the output.
+pub const NULL_PATHS_EXTENSION: &str = "x-verge-null-paths";
+
+/// Extension of the `info` object naming operations that lack an operation
+/// ID, like `{ "GET /users": "list_users" }`, see
+/// [`crate::settings::Settings::info_operation_names`]
+pub const OPERATION_NAMES_EXTENSION: &str = "x-operation-names";
+
+/// The operation IDs [`OPERATION_NAMES_EXTENSION`] gives, keyed by path and
+/// lowercase method. Fails if an entry is not a known method and a path
+/// mapped to an operation ID.
+pub fn operation_names(spec: &OpenAPI) -> Result<BTreeMap<(String, String), String>> {
+  let Some(names) = spec.info.extensions.get(OPERATION_NAMES_EXTENSION) else {
+    return Ok(BTreeMap::new());
+  };
+  let names = names.as_object().ok_or_else(|| {
+    anyhow!("{OPERATION_NAMES_EXTENSION} must map \"METHOD /path\" to operation ids")
+  })?;
+  names
+    .iter()
+    .map(|(endpoint, name)| {
+      let (method, path) = endpoint
+        .split_once(' ')
+        .map(|(method, path)| (method.to_lowercase(), path.trim()))
+        .filter(|(method, path)| {
+          METHODS.contains(&method.as_str()) && path.starts_with('/')
+        })
+        .ok_or_else(|| {
+          anyhow!(
+            "invalid {OPERATION_NAMES_EXTENSION} entry {endpoint:?}, expected \
+             \"METHOD /path\""
+          )
+        })?;
+      let name = name
+        .as_str()
+        .filter(|name| !name.is_empty())
+        .ok_or_else(|| {
+          anyhow!(
+            "{OPERATION_NAMES_EXTENSION} entry {endpoint:?} is no operation id: {name}"
+          )
+        })?;
+      Ok(((path.to_string(), method), name.to_string()))
+    })
+    .collect()
+}
+
+/// Copy of a path item with all of its operations removed, keeping the
+/// path level fields like shared parameters and servers.
+pub fn without_operations(item: &PathItem) -> PathItem {
+  PathItem {
+    get: None,
+    put: None,
+    post: None,
+    delete: None,
+    options: None,
+    head: None,
+    patch: None,
+    trace: None,
+    ..item.clone()
+  }
+}
+
+/// Build a minimal spec around the given JSON `paths` object
+#[cfg(test)]
+pub fn mk_spec(paths: &str) -> OpenAPI {
+  serde_json::from_str(&format!(
+    r#"{{
+      "openapi": "3.0.1",
+      "info": {{ "title": "test", "version": "1" }},
+      "paths": {paths}
+    }}"#
+  ))
+  .unwrap()
+}
+
+#[test]
+fn test_remove_internal() {
+  let mut spec = mk_spec(
+    r#"{
+      "/users": {
+        "get": { "responses": {} },
+        "post": { "x-internal": true, "responses": {} },
+        "delete": { "x-internal": "true", "responses": {} },
+        "patch": { "x-internal": false, "responses": {} }
+      },
+      "/debug": { "get": { "x-internal": "TRUE", "responses": {} } },
+      "/empty": {}
+    }"#,
+  );
+  assert_eq!(remove_internal(&mut spec), 3);
+  assert_eq!(
+    spec.paths.paths.keys().collect::<Vec<_>>(),
+    ["/users", "/empty"]
+  );
+  let users = spec.paths.paths["/users"].as_item().unwrap();
+  assert_eq!(
+    users.iter().map(|(method, _)| method).collect::<Vec<_>>(),
+    ["get", "patch"]
+  );
+}
@@ -0,0 +1,231 @@
+use anyhow::{anyhow, Result};
+use openapiv3::{OpenAPI, Operation, ReferenceOr};
+
+use std::collections::BTreeMap;
+
+use crate::opid::{OperationIds, Origin};
+use crate::spec::{operation_slot, without_operations};
+
+/// Split a spec into one sub-spec per tag. Every sub-spec contains only the
+/// operations carrying its tag, plus a full copy of everything outside of
+/// `paths` (components, servers, security, ...), so references into the
+/// shared components keep resolving.
+///
+/// Operations with several tags end up in several sub-specs, untagged
+/// operations in none of them. Operation IDs are left untouched, so they can
+/// be generated for every sub-spec independently.
+pub fn split_by_tag(spec: &OpenAPI) -> BTreeMap<String, OpenAPI> {
+  let mut skeleton = spec.clone();
+  skeleton.paths.paths.clear();
+
+  let mut sub_specs: BTreeMap<String, OpenAPI> = BTreeMap::new();
+  for (path, item) in spec.paths.paths.iter() {
+    let Some(item) = item.as_item() else {
+      continue;
+    };
+    for (method, op) in item.iter() {
+      for tag in &op.tags {
+        let sub_spec = sub_specs.entry(tag.clone()).or_insert_with(|| {
+          let mut sub_spec = skeleton.clone();
+          sub_spec.tags.retain(|t| &t.name == tag);
+          sub_spec
+        });
+        let sub_item = sub_spec
+          .paths
+          .paths
+          .entry(path.clone())
+          .or_insert_with(|| ReferenceOr::Item(without_operations(item)));
+        if let Some(slot) = sub_item
+          .as_item_mut()
+          .and_then(|sub_item| operation_slot(sub_item, method))
+        {
+          *slot = Some(op.clone());
+        }
+      }
+    }
+  }
+  sub_specs
+}
+
+/// Trim a spec down to the operations that got synthetic operation IDs, for
+/// reviewing the generated names without the rest. Like with
+/// [`split_by_tag`], everything outside of `paths` is kept, so the result
+/// is still a valid spec. The operations carry their synthetic IDs even if
+/// they were not written back to `spec`.
+pub fn synthetic_only(spec: &OpenAPI, opids: &OperationIds) -> OpenAPI {
+  let mut trimmed = spec.clone();
+  trimmed.paths.paths.clear();
+  for (path, item) in spec.paths.paths.iter() {
+    let Some(item) = item.as_item() else {
+      continue;
+    };
+    for (method, op) in item.iter() {
+      let Some(opid) = opids
+        .opid_for_path_method(path, method)
+        .filter(|opid| opids.origin_of(opid) == Some(Origin::Synthetic))
+      else {
+        continue;
+      };
+      let trimmed_item = trimmed
+        .paths
+        .paths
+        .entry(path.clone())
+        .or_insert_with(|| ReferenceOr::Item(without_operations(item)));
+      if let Some(slot) = trimmed_item
+        .as_item_mut()
+        .and_then(|trimmed_item| operation_slot(trimmed_item, method))
+      {
+        *slot = Some(Operation {
+          operation_id: Some(opid.to_string()),
+          ..op.clone()
+        });
+      }
+    }
+  }
+  trimmed
+}
+
+/// File name for the sub-spec of a tag. Characters that are not safe in
+/// file names are replaced by `_`.
+pub fn file_name(tag: &str) -> String {
+  let stem: String = tag
+    .chars()
+    .map(|c| {
+      if c.is_alphanumeric() || c == '-' || c == '_' {
+        c
+      } else {
+        '_'
+      }
+    })
+    .collect();
+  format!("{stem}.json")
+}
+
+/// The [`file_name`] of every tag, keyed by tag. Fails if two tags would be
+/// written to the same file, which is checked before anything is written.
+/// File names only differing in case count as the same, since
+/// case-insensitive file systems do not tell them apart.
+pub fn file_names<'a>(
+  tags: impl IntoIterator<Item = &'a String>,
+) -> Result<BTreeMap<String, String>> {
+  let mut taken: BTreeMap<String, &str> = BTreeMap::new();
+  let mut names = BTreeMap::new();
+  for tag in tags {
+    let name = file_name(tag);
+    if let Some(other) = taken.insert(name.to_lowercase(), tag) {
+      return Err(anyhow!(
+        "tags {other:?} and {tag:?} would both be written to {name}"
+      ));
+    }
+    names.insert(tag.clone(), name);
+  }
+  Ok(names)
+}
+
+#[test]
+fn test_split_by_tag() {
+  let spec = r##"{
+    "openapi": "3.0.1",
+    "info": { "title": "test", "version": "1" },
+    "tags": [ { "name": "users" }, { "name": "admin" } ],
+    "paths": {
+      "/users": {
+        "get": { "tags": ["users", "admin"], "responses": {} },
+        "post": { "tags": ["admin"], "responses": {} },
+        "delete": { "responses": {} }
+      },
+      "/users/{id}": {
+        "get": { "tags": ["users"], "operationId": "user_get", "responses": {} }
+      }
+    },
+    "components": {
+      "schemas": { "User": { "type": "object" } }
+    }
+  }"##;
+  let spec: OpenAPI = serde_json::from_str(spec).unwrap();
+
+  let mut sub_specs = split_by_tag(&spec);
+  assert_eq!(sub_specs.keys().collect::<Vec<_>>(), vec!["admin", "users"]);
+  sub_specs.values_mut().for_each(|sub_spec| {
+    crate::generate::gen_operation_ids(sub_spec, &Default::default()).unwrap();
+  });
+
+  // GET /users carries both tags and must show up in both sub-specs
+  let endpoints = |tag: &str| {
+    crate::generate::collect_opids(&sub_specs[tag])
+      .into_iter()
+      .map(|((path, method), opid)| format!("{method} {path} {}", opid.unwrap()))
+      .collect::<Vec<_>>()
+  };
+  assert_eq!(
+    endpoints("admin"),
+    ["get /users users_get", "post /users users_post"]
+  );
+  assert_eq!(
+    endpoints("users"),
+    ["get /users users_get", "get /users/{id} user_get"]
+  );
+
+  // everything outside of paths is shared, tags are narrowed down
+  let users = &sub_specs["users"];
+  assert!(users
+    .components
+    .as_ref()
+    .unwrap()
+    .schemas
+    .contains_key("User"));
+  assert_eq!(
+    users
+      .tags
+      .iter()
+      .map(|t| t.name.as_str())
+      .collect::<Vec<_>>(),
+    vec!["users"]
+  );
+}
+
+#[test]
+fn test_file_name() {
+  assert_eq!(file_name("users"), "users.json");
+  assert_eq!(file_name("auth/sources v2"), "auth_sources_v2.json");
+
+  let tags = ["a/b".to_string(), "users".to_string()];
+  assert_eq!(file_names(&tags).unwrap()["a/b"], "a_b.json");
+  let tags = ["a/b".to_string(), "a_b".to_string()];
+  assert_eq!(
+    file_names(&tags).unwrap_err().to_string(),
+    "tags \"a/b\" and \"a_b\" would both be written to a_b.json"
+  );
+  let tags = ["Users".to_string(), "users".to_string()];
+  assert!(file_names(&tags).is_err());
+}
+
+#[test]
+fn test_synthetic_only() {
+  let mut spec = crate::spec::mk_spec(
+    r#"{
+      "/users": {
+        "get": { "responses": {} },
+        "post": { "operationId": "create_user", "responses": {} }
+      },
+      "/groups": {
+        "get": { "operationId": "list_groups", "responses": {} }
+      }
+    }"#,
+  );
+  let settings = crate::settings::Settings {
+    write_back: false,
+    ..Default::default()
+  };
+  let opids = crate::generate::gen_operation_ids(&mut spec, &settings).unwrap();
+
+  let trimmed = synthetic_only(&spec, &opids);
+  assert_eq!(
+    crate::generate::collect_opids(&trimmed)
+      .into_iter()
+      .map(|((path, method), opid)| format!("{method} {path} {}", opid.unwrap()))
+      .collect::<Vec<_>>(),
+    ["get /users users_get"]
+  );
+  assert_eq!(trimmed.info.title, "test");
+}
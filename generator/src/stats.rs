@@ -0,0 +1,142 @@
+use anyhow::Result;
+use openapiv3::OpenAPI;
+use serde::Serialize;
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::io::Write;
+use std::path::Path;
+
+use crate::output;
+
+/// Write `stats` to `path` as pretty printed JSON, replacing the file
+pub fn write_stats(path: &Path, stats: &Stats) -> Result<()> {
+  output::write_output(path, true, |writer| {
+    serde_json::to_writer_pretty(&mut *writer, stats)?;
+    Ok(writeln!(writer)?)
+  })
+}
+
+/// Where [`tag_counts`] counts operations without tags
+pub const UNTAGGED: &str = "(untagged)";
+
+/// Summary of how the operation IDs of a spec were assigned
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize)]
+pub struct Stats {
+  /// Operation IDs taken from the spec
+  pub explicit: usize,
+  /// Operation IDs generated from paths and methods
+  pub synthetic: usize,
+  /// Synthetic operation IDs that needed a collision suffix
+  pub collisions: usize,
+  /// Highest collision suffix any synthetic operation ID carries
+  pub max_attempt: u32,
+}
+
+impl fmt::Display for Stats {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(
+      f,
+      "{} operation ids: {} explicit, {} synthetic, {} with a collision suffix \
+       (max attempt {})",
+      self.explicit + self.synthetic,
+      self.explicit,
+      self.synthetic,
+      self.collisions,
+      self.max_attempt
+    )
+  }
+}
+
+/// The number of operations of `spec` per tag, sorted by tag. Operations
+/// with several tags count once for each of them, those without any under
+/// [`UNTAGGED`].
+pub fn tag_counts(spec: &OpenAPI) -> BTreeMap<String, usize> {
+  let mut counts = BTreeMap::new();
+  spec
+    .paths
+    .paths
+    .values()
+    .filter_map(|item| item.as_item())
+    .flat_map(|item| item.iter())
+    .for_each(|(_, op)| {
+      if op.tags.is_empty() {
+        *counts.entry(UNTAGGED.to_string()).or_default() += 1;
+      }
+      for tag in &op.tags {
+        *counts.entry(tag.clone()).or_default() += 1;
+      }
+    });
+  counts
+}
+
+/// [`tag_counts`] in readable form, one tag per line
+pub fn format_tag_counts(counts: &BTreeMap<String, usize>) -> String {
+  let width = counts.keys().map(String::len).max().unwrap_or_default();
+  let mut formatted = String::from("operations per tag:\n");
+  for (tag, count) in counts {
+    formatted += &format!("  {tag:<width$} {count:>5}\n");
+  }
+  formatted
+}
+
+#[test]
+fn test_tag_counts() {
+  let spec = crate::spec::mk_spec(
+    r#"{
+      "/users": {
+        "get": { "tags": ["users", "admin"], "responses": {} },
+        "post": { "tags": ["admin"], "responses": {} },
+        "delete": { "responses": {} }
+      },
+      "/health": { "get": { "responses": {} } }
+    }"#,
+  );
+  let counts = tag_counts(&spec);
+  assert_eq!(
+    counts
+      .iter()
+      .map(|(tag, count)| (tag.as_str(), *count))
+      .collect::<Vec<_>>(),
+    [("(untagged)", 2), ("admin", 2), ("users", 1)]
+  );
+  assert_eq!(
+    format_tag_counts(&counts),
+    "operations per tag:\n  (untagged)     2\n  admin          2\n  users          1\n"
+  );
+}
+
+#[test]
+fn test_stats() {
+  let mut spec = crate::spec::mk_spec(
+    r#"{
+      "/users": {
+        "get": { "operationId": "list_users", "responses": {} },
+        "post": { "responses": {} }
+      },
+      "/users_": { "post": { "responses": {} } },
+      "/users-": { "post": { "responses": {} } }
+    }"#,
+  );
+  let stats = crate::generate::gen_operation_ids(&mut spec, &Default::default())
+    .unwrap()
+    .stats();
+  assert_eq!(
+    stats,
+    Stats {
+      explicit: 1,
+      synthetic: 3,
+      collisions: 2,
+      max_attempt: 2,
+    }
+  );
+  assert_eq!(
+    serde_json::to_value(&stats).unwrap(),
+    serde_json::json!({
+      "explicit": 1,
+      "synthetic": 3,
+      "collisions": 2,
+      "max_attempt": 2,
+    })
+  );
+}
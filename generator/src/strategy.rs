@@ -0,0 +1,140 @@
+//! Hook for embedders that want to name operations themselves.
+
+use openapiv3::Operation;
+
+use std::collections::BTreeMap;
+
+use crate::opid::OperationIds;
+
+/// Picks the operation ID of an operation lacking one, with the full
+/// operation and the operation IDs assigned so far at hand.
+/// [`crate::generate::gen_operation_ids_with_strategy`] consults it for
+/// every such operation, in canonical path and method order, after all
+/// explicit operation IDs have been registered.
+pub trait IdStrategy {
+  /// The operation ID for `method` on `path`, or [`None`] to fall back to
+  /// the synthetic ID the generator would assign by default. Returning an
+  /// ID that is taken already is an error.
+  fn id(
+    &self,
+    path: &str,
+    method: &str,
+    op: &Operation,
+    ids: &OperationIds,
+  ) -> Option<String>;
+
+  /// Word to append to the synthetic ID of `method` on `path` when
+  /// [`Self::id`] returned [`None`], like the media type of its response,
+  /// to tell apart operations that would otherwise only differ in their
+  /// collision suffix. The default appends nothing.
+  fn discriminator(&self, _path: &str, _method: &str, _op: &Operation) -> Option<String> {
+    None
+  }
+}
+
+/// The behavior of the generator without customization: every operation
+/// gets the default synthetic ID
+#[derive(Clone, Copy, Debug, Default)]
+pub struct DefaultStrategy;
+
+impl IdStrategy for DefaultStrategy {
+  fn id(
+    &self,
+    _path: &str,
+    _method: &str,
+    _op: &Operation,
+    _ids: &OperationIds,
+  ) -> Option<String> {
+    None
+  }
+}
+
+/// Appends a word for the content type of the request body, like
+/// `upload_post_multipart` for `multipart/form-data`, so endpoints that
+/// only differ in what they accept tell apart. Only request bodies with a
+/// single content type get one, bodies that are references are skipped.
+/// Picked by [`crate::generate::gen_operation_ids`] with
+/// [`crate::settings::Settings::request_content_type`].
+#[derive(Clone, Copy, Debug, Default)]
+pub struct ByRequestContentType;
+
+impl IdStrategy for ByRequestContentType {
+  fn id(
+    &self,
+    _path: &str,
+    _method: &str,
+    _op: &Operation,
+    _ids: &OperationIds,
+  ) -> Option<String> {
+    None
+  }
+
+  fn discriminator(&self, _path: &str, _method: &str, op: &Operation) -> Option<String> {
+    let content = &op.request_body.as_ref()?.as_item()?.content;
+    if content.len() != 1 {
+      return None;
+    }
+    let (content_type, _) = content.first()?;
+    Some(content_type_word(content_type))
+  }
+}
+
+/// Names the endpoints of a name map, keyed by path and lowercase method,
+/// like the one of [`crate::spec::OPERATION_NAMES_EXTENSION`], and leaves
+/// the others to `fallback`
+pub struct NameMap {
+  pub names: BTreeMap<(String, String), String>,
+  pub fallback: Box<dyn IdStrategy>,
+}
+
+impl IdStrategy for NameMap {
+  fn id(
+    &self,
+    path: &str,
+    method: &str,
+    op: &Operation,
+    ids: &OperationIds,
+  ) -> Option<String> {
+    match self.names.get(&(path.to_string(), method.to_string())) {
+      Some(name) => Some(name.clone()),
+      None => self.fallback.id(path, method, op, ids),
+    }
+  }
+
+  fn discriminator(&self, path: &str, method: &str, op: &Operation) -> Option<String> {
+    self.fallback.discriminator(path, method, op)
+  }
+}
+
+/// A short word for `content_type`, like `json` for `application/json` or
+/// `application/problem+json`, and the subtype for uncommon ones
+fn content_type_word(content_type: &str) -> String {
+  let essence = content_type
+    .split(';')
+    .next()
+    .unwrap_or_default()
+    .trim()
+    .to_lowercase();
+  let (kind, subtype) = essence.split_once('/').unwrap_or(("", &essence));
+  match (kind, subtype) {
+    ("multipart", _) => "multipart".to_string(),
+    (_, "json") | (_, "x-ndjson") => "json".to_string(),
+    (_, subtype) if subtype.ends_with("+json") => "json".to_string(),
+    (_, "x-www-form-urlencoded") => "form".to_string(),
+    (_, "octet-stream") => "binary".to_string(),
+    (_, subtype) => subtype.to_string(),
+  }
+}
+
+#[test]
+fn test_content_type_word() {
+  assert_eq!(content_type_word("multipart/form-data"), "multipart");
+  assert_eq!(content_type_word("application/json; charset=utf-8"), "json");
+  assert_eq!(content_type_word("application/merge-patch+json"), "json");
+  assert_eq!(
+    content_type_word("application/x-www-form-urlencoded"),
+    "form"
+  );
+  assert_eq!(content_type_word("application/octet-stream"), "binary");
+  assert_eq!(content_type_word("text/csv"), "csv");
+}
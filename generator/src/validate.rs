@@ -0,0 +1,405 @@
+use anyhow::{anyhow, Result};
+use openapiv3::{OpenAPI, Parameter, ReferenceOr};
+
+use std::collections::BTreeSet;
+
+use crate::normalize::normalize_path;
+use crate::opid::extract_params;
+#[cfg(test)]
+use crate::spec::mk_spec;
+use crate::spec::NULL_PATHS_EXTENSION;
+use crate::warning::Warning;
+
+/// Sanity check a deserialized spec before operation IDs are generated.
+///
+/// This is not a validation against the OpenAPI schema, it only catches
+/// problems that would otherwise surface as odd operation IDs much later:
+/// every path has to start with `/`. All problems are reported together.
+pub fn validate_spec(spec: &OpenAPI) -> Result<()> {
+  let mut problems = Vec::new();
+
+  for path in spec.paths.paths.keys() {
+    if !path.starts_with('/') {
+      problems.push(format!("path {path:?} does not start with \"/\""));
+    }
+  }
+
+  if problems.is_empty() {
+    Ok(())
+  } else {
+    Err(anyhow!("invalid spec:\n  {}", problems.join("\n  ")))
+  }
+}
+
+/// Name of a path parameter, looking references up in the components.
+/// Returns [`None`] for parameters that are not in the path, and for
+/// references that do not resolve.
+fn path_param_name<'a>(
+  spec: &'a OpenAPI,
+  param: &'a ReferenceOr<Parameter>,
+) -> Option<Option<&'a str>> {
+  let param = match param {
+    ReferenceOr::Item(param) => param,
+    ReferenceOr::Reference { reference } => {
+      let name = reference.strip_prefix("#/components/parameters/")?;
+      spec.components.as_ref()?.parameters.get(name)?.as_item()?
+    }
+  };
+  Some(match param {
+    Parameter::Path { parameter_data, .. } => Some(parameter_data.name.as_str()),
+    _ => None,
+  })
+}
+
+/// Compare the `{param}`s of every path with the path parameters its
+/// operations declare. Parameters declared on the path item count for all
+/// of its operations. Operations with parameter references that do not
+/// resolve are skipped, since their parameters are unknown.
+pub fn check_path_params(spec: &OpenAPI) -> Vec<Warning> {
+  let mut warnings = Vec::new();
+  for (path, item) in spec.paths.paths.iter() {
+    let Some(item) = item.as_item() else {
+      continue;
+    };
+    let in_path: BTreeSet<&str> = extract_params(path)
+      .map(|(params, _)| params.into_iter().collect())
+      .unwrap_or_default();
+
+    for (method, op) in item.iter() {
+      let Some(declared) = item
+        .parameters
+        .iter()
+        .chain(&op.parameters)
+        .map(|param| path_param_name(spec, param))
+        .collect::<Option<Vec<_>>>()
+      else {
+        continue;
+      };
+      let declared: BTreeSet<&str> = declared.into_iter().flatten().collect();
+
+      let endpoint = format!("{} {path}", method.to_uppercase());
+      in_path.difference(&declared).for_each(|param| {
+        warnings.push(Warning::new(format!(
+          "{endpoint} does not declare path parameter {param:?}"
+        )));
+      });
+      declared.difference(&in_path).for_each(|param| {
+        warnings.push(Warning::new(format!(
+          "{endpoint} declares path parameter {param:?}, which is not in the path"
+        )));
+      });
+    }
+  }
+  warnings
+}
+
+/// Paths whose path item has no operations. Generated clients would carry
+/// them as dead paths. Path items that are references are not counted.
+fn empty_path_items(spec: &OpenAPI) -> Vec<String> {
+  spec
+    .paths
+    .paths
+    .iter()
+    .filter(|(_, item)| {
+      item
+        .as_item()
+        .is_some_and(|item| item.iter().next().is_none())
+    })
+    .map(|(path, _)| path.clone())
+    .collect()
+}
+
+/// The `major.minor` OpenAPI versions the openapiv3 crate reads faithfully.
+/// Specs of other versions may deserialize, but lose or misread parts.
+pub const SUPPORTED_VERSIONS: [(u32, u32); 1] = [(3, 0)];
+
+/// The major and minor version of an `openapi` field like `3.0.1`
+fn parse_version(version: &str) -> Option<(u32, u32)> {
+  let mut parts = version.trim().split('.');
+  let major = parts.next()?.parse().ok()?;
+  let minor = parts.next()?.parse().ok()?;
+  Some((major, minor))
+}
+
+/// What is wrong with the OpenAPI version of the spec, if it is not one of
+/// the [`SUPPORTED_VERSIONS`]
+fn unsupported_version(spec: &OpenAPI) -> Option<String> {
+  let supported = SUPPORTED_VERSIONS
+    .iter()
+    .map(|(major, minor)| format!("{major}.{minor}.x"))
+    .collect::<Vec<_>>()
+    .join(", ");
+  match parse_version(&spec.openapi) {
+    Some(version) if SUPPORTED_VERSIONS.contains(&version) => None,
+    Some(_) => Some(format!(
+      "OpenAPI version {} is not supported, only {supported} is, so parts of the \
+       spec may be misread",
+      spec.openapi
+    )),
+    None => Some(format!("invalid OpenAPI version {:?}", spec.openapi)),
+  }
+}
+
+/// Warn about an unsupported OpenAPI version, unless `--strict` rejects it
+pub fn warn_unsupported_version(spec: &OpenAPI) -> Vec<Warning> {
+  unsupported_version(spec)
+    .map(Warning::new)
+    .into_iter()
+    .collect()
+}
+
+/// Fail if the OpenAPI version of the spec is unsupported, for `--strict`
+pub fn reject_unsupported_version(spec: &OpenAPI) -> Result<()> {
+  match unsupported_version(spec) {
+    Some(problem) => Err(anyhow!(problem)),
+    None => Ok(()),
+  }
+}
+
+/// The checks every spec read gets, however it is processed: fails on what
+/// [`validate_spec`] finds, and on `null` path items and an unsupported
+/// OpenAPI version when `strict` is set, which are warnings otherwise
+pub fn check_spec(spec: &mut OpenAPI, strict: bool) -> Result<Vec<Warning>> {
+  let mut warnings = skip_null_path_items(spec, strict)?;
+  validate_spec(spec)?;
+  if strict {
+    reject_unsupported_version(spec)?;
+    return Ok(warnings);
+  }
+  warnings.extend(warn_unsupported_version(spec));
+  Ok(warnings)
+}
+
+/// Take the paths whose `null` path items were removed while reading the
+/// spec out of [`NULL_PATHS_EXTENSION`]. Fails on them when `strict` is
+/// set, otherwise each is a warning.
+pub fn skip_null_path_items(spec: &mut OpenAPI, strict: bool) -> Result<Vec<Warning>> {
+  let paths: Vec<String> = spec
+    .extensions
+    .shift_remove(NULL_PATHS_EXTENSION)
+    .and_then(|paths| serde_json::from_value(paths).ok())
+    .unwrap_or_default();
+  if strict && !paths.is_empty() {
+    return Err(anyhow!("null path items: {}", paths.join(", ")));
+  }
+  Ok(
+    paths
+      .into_iter()
+      .map(|path| Warning::new(format!("skipped path {path}, its path item is null")))
+      .collect(),
+  )
+}
+
+/// Warn about path items without operations, when neither `--strict` nor
+/// `--prune-empty` decides what to do with them. They are kept as they are.
+pub fn warn_empty_path_items(spec: &OpenAPI) -> Vec<Warning> {
+  empty_path_items(spec)
+    .into_iter()
+    .map(|path| {
+      Warning::new(format!(
+        "path {path} has no operations, --prune-empty removes it"
+      ))
+    })
+    .collect()
+}
+
+/// Fail if a path item of the spec has no operations, for `--strict`
+pub fn reject_empty_path_items(spec: &OpenAPI) -> Result<()> {
+  let empty = empty_path_items(spec);
+  if empty.is_empty() {
+    Ok(())
+  } else {
+    Err(anyhow!("paths without operations: {}", empty.join(", ")))
+  }
+}
+
+/// Fail if a path has more than `max_depth` segments, for
+/// `--max-path-depth`. Such deep paths are mostly a bug of the spec, and
+/// make for unusable operation IDs. Segments are counted on the normalized
+/// path, so `//a//b/` has two.
+pub fn check_path_depth(spec: &OpenAPI, max_depth: usize) -> Result<()> {
+  for path in spec.paths.paths.keys() {
+    let depth = normalize_path(path)
+      .split('/')
+      .filter(|segment| !segment.is_empty())
+      .count();
+    if depth > max_depth {
+      return Err(anyhow!(
+        "path {path} has {depth} segments, more than the maximum of {max_depth}"
+      ));
+    }
+  }
+  Ok(())
+}
+
+/// Remove the path items without operations from the spec, for
+/// `--prune-empty`. Returns the removed paths.
+pub fn prune_empty_path_items(spec: &mut OpenAPI) -> Vec<String> {
+  let empty = empty_path_items(spec);
+  spec.paths.paths.retain(|path, _| !empty.contains(path));
+  empty
+}
+
+#[test]
+fn test_validate_spec() {
+  let spec = mk_spec(r#"{ "/users": { "get": { "responses": {} } } }"#);
+  assert!(validate_spec(&spec).is_ok());
+
+  let spec = mk_spec(
+    r#"{
+      "/users": { "get": { "responses": {} } },
+      "users/{id}": { "get": { "responses": {} } }
+    }"#,
+  );
+  let err = validate_spec(&spec).unwrap_err().to_string();
+  assert_eq!(
+    err,
+    "invalid spec:\n  path \"users/{id}\" does not start with \"/\""
+  );
+}
+
+#[test]
+fn test_unsupported_version() {
+  let mut spec = mk_spec("{}");
+  assert!(warn_unsupported_version(&spec).is_empty());
+  assert!(reject_unsupported_version(&spec).is_ok());
+
+  spec.openapi = "3.1.0".to_string();
+  assert_eq!(
+    warn_unsupported_version(&spec),
+    [Warning::new(
+      "OpenAPI version 3.1.0 is not supported, only 3.0.x is, so parts of the spec \
+       may be misread"
+    )]
+  );
+  assert!(reject_unsupported_version(&spec).is_err());
+
+  spec.openapi = "three".to_string();
+  assert_eq!(
+    reject_unsupported_version(&spec).unwrap_err().to_string(),
+    "invalid OpenAPI version \"three\""
+  );
+}
+
+#[test]
+fn test_check_spec() {
+  let mut spec = mk_spec(r#"{ "/users": { "get": { "responses": {} } } }"#);
+  assert_eq!(check_spec(&mut spec, true).unwrap(), []);
+
+  spec.openapi = "3.1.0".to_string();
+  assert_eq!(check_spec(&mut spec, false).unwrap().len(), 1);
+  assert!(check_spec(&mut spec, true).is_err());
+
+  let mut spec = mk_spec(r#"{ "users": { "get": { "responses": {} } } }"#);
+  assert!(check_spec(&mut spec, false).is_err());
+}
+
+#[test]
+fn test_empty_path_items() {
+  let paths = r#"{
+    "/users": { "get": { "responses": {} } },
+    "/legacy": { "parameters": [] },
+    "/old": {}
+  }"#;
+  let err = reject_empty_path_items(&mk_spec(paths)).unwrap_err();
+  assert_eq!(err.to_string(), "paths without operations: /legacy, /old");
+
+  let mut spec = mk_spec(paths);
+  assert_eq!(prune_empty_path_items(&mut spec), ["/legacy", "/old"]);
+  assert_eq!(spec.paths.paths.keys().collect::<Vec<_>>(), ["/users"]);
+  assert!(reject_empty_path_items(&spec).is_ok());
+}
+
+#[test]
+fn test_null_path_items() {
+  let read = || {
+    crate::input::parse_spec(
+      r#"{
+      "openapi": "3.0.1",
+      "info": { "title": "test", "version": "1" },
+      "paths": { "/users": { "get": { "responses": {} } }, "/foo": null }
+    }"#,
+      false,
+    )
+    .unwrap()
+  };
+  let mut spec = read();
+  assert_eq!(
+    check_spec(&mut spec, false).unwrap(),
+    [Warning::new("skipped path /foo, its path item is null")]
+  );
+  assert_eq!(spec.paths.paths.keys().collect::<Vec<_>>(), ["/users"]);
+  assert!(spec.extensions.is_empty());
+  assert_eq!(warn_empty_path_items(&spec), []);
+  assert_eq!(
+    check_spec(&mut read(), true).unwrap_err().to_string(),
+    "null path items: /foo"
+  );
+}
+
+#[test]
+fn test_check_path_params() {
+  let spec: OpenAPI = serde_json::from_str(
+    r##"{
+      "openapi": "3.0.1",
+      "info": { "title": "test", "version": "1" },
+      "paths": {
+        "/users/{id}/posts/{post}": {
+          "parameters": [
+            { "name": "id", "in": "path", "required": true, "schema": { "type": "string" } }
+          ],
+          "get": {
+            "parameters": [ { "$ref": "#/components/parameters/Post" } ],
+            "responses": {}
+          },
+          "delete": {
+            "parameters": [
+              { "name": "force", "in": "query", "schema": { "type": "boolean" } },
+              { "name": "extra", "in": "path", "required": true, "schema": { "type": "string" } }
+            ],
+            "responses": {}
+          },
+          "put": {
+            "parameters": [ { "$ref": "#/components/parameters/Unknown" } ],
+            "responses": {}
+          }
+        }
+      },
+      "components": {
+        "parameters": {
+          "Post": { "name": "post", "in": "path", "required": true, "schema": { "type": "string" } }
+        }
+      }
+    }"##,
+  )
+  .unwrap();
+  // `id` comes from the path item and `post` from the operation, so
+  // GET is fine, and PUT cannot be checked
+  assert_eq!(
+    check_path_params(&spec)
+      .into_iter()
+      .map(|warning| warning.message)
+      .collect::<Vec<_>>(),
+    [
+      "DELETE /users/{id}/posts/{post} does not declare path parameter \"post\"",
+      "DELETE /users/{id}/posts/{post} declares path parameter \"extra\", which is \
+       not in the path",
+    ]
+  );
+}
+
+#[test]
+fn test_check_path_depth() {
+  let spec = mk_spec(
+    r#"{
+      "/": { "get": { "responses": {} } },
+      "//a//b/{id}/": { "get": { "responses": {} } }
+    }"#,
+  );
+  assert!(check_path_depth(&spec, 3).is_ok());
+  let err = check_path_depth(&spec, 2).unwrap_err();
+  assert_eq!(
+    err.to_string(),
+    "path //a//b/{id}/ has 3 segments, more than the maximum of 2"
+  );
+}
@@ -0,0 +1,80 @@
+use std::fmt;
+use std::path::Path;
+
+/// Advisory problem found while processing a spec. Warnings never change
+/// the output, they are reported once processing is done.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Warning {
+  pub message: String,
+}
+
+impl Warning {
+  pub fn new(message: impl Into<String>) -> Self {
+    Self {
+      message: message.into(),
+    }
+  }
+}
+
+impl fmt::Display for Warning {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(f, "warning: {}", self.message)
+  }
+}
+
+/// How warnings are printed
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum WarningFormat {
+  /// `warning: message`
+  #[default]
+  Plain,
+  /// `::warning file=spec.json::message`, which GitHub Actions shows as an
+  /// annotation of the spec. Since problems cannot be traced to lines of
+  /// the spec, the message names the endpoint instead.
+  GitHub,
+}
+
+impl WarningFormat {
+  /// `warning` about the spec at `file` the way this format prints it
+  pub fn format(self, warning: &Warning, file: &Path) -> String {
+    match self {
+      WarningFormat::Plain => warning.to_string(),
+      WarningFormat::GitHub => format!(
+        "::warning file={}::{}",
+        escape_property(&file.display().to_string()),
+        escape_data(&warning.message)
+      ),
+    }
+  }
+}
+
+/// Escape the message of a workflow command
+fn escape_data(data: &str) -> String {
+  data
+    .replace('%', "%25")
+    .replace('\r', "%0D")
+    .replace('\n', "%0A")
+}
+
+/// Escape a property of a workflow command, like its file
+fn escape_property(property: &str) -> String {
+  escape_data(property)
+    .replace(':', "%3A")
+    .replace(',', "%2C")
+}
+
+#[test]
+fn test_github_annotation() {
+  let warning =
+    Warning::new("operation id \"users_get\" of GET /users is 100% long,\nreally");
+  let file = Path::new("specs/v4,final.json");
+  assert_eq!(
+    WarningFormat::Plain.format(&warning, file),
+    "warning: operation id \"users_get\" of GET /users is 100% long,\nreally"
+  );
+  assert_eq!(
+    WarningFormat::GitHub.format(&warning, file),
+    "::warning file=specs/v4%2Cfinal.json::operation id \"users_get\" of GET /users is \
+     100%25 long,%0Areally"
+  );
+}
@@ -0,0 +1,49 @@
+use anyhow::Result;
+use notify::{EventKind, RecursiveMode, Watcher};
+
+use std::path::Path;
+use std::sync::mpsc;
+use std::time::Duration;
+
+/// How long the input has to stay untouched before regenerating. Editors
+/// tend to save in several steps, which should only trigger one run.
+const DEBOUNCE: Duration = Duration::from_millis(300);
+
+/// Run `regenerate` once, and then again every time the file at `input`
+/// changes, until the process is interrupted. Errors of single runs are
+/// reported without ending the watch, so a broken intermediate save does
+/// not need a restart.
+pub fn watch(input: &Path, mut regenerate: impl FnMut() -> Result<()>) -> Result<()> {
+  let (tx, rx) = mpsc::channel();
+  let mut watcher = notify::recommended_watcher(tx)?;
+  // Watch the directory rather than the file itself: many editors save by
+  // replacing the file, which would end a watch on the file.
+  let dir = match input.parent() {
+    Some(dir) if !dir.as_os_str().is_empty() => dir,
+    _ => Path::new("."),
+  };
+  watcher.watch(dir, RecursiveMode::NonRecursive)?;
+
+  let run = |regenerate: &mut dyn FnMut() -> Result<()>| {
+    if let Err(err) = regenerate() {
+      eprintln!("error: {err:#}");
+    }
+  };
+  run(&mut regenerate);
+  eprintln!("watching {} for changes", input.display());
+
+  loop {
+    let event = rx.recv()??;
+    let touches_input = !matches!(event.kind, EventKind::Access(_))
+      && event
+        .paths
+        .iter()
+        .any(|path| path.file_name() == input.file_name());
+    if !touches_input {
+      continue;
+    }
+
+    while rx.recv_timeout(DEBOUNCE).is_ok() {}
+    run(&mut regenerate);
+  }
+}